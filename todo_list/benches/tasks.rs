@@ -0,0 +1,132 @@
+// 10 万个任务时，索引结构和原来的线性扫描对比
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use todo_list::filter::Filter;
+use todo_list::task::{Priority, Status, Task};
+use todo_list::tasks::Tasks;
+use todo_list::todo::TodoList;
+
+const COUNT: usize = 100_000;
+
+fn sample_tasks() -> Vec<Task> {
+    let priorities = [Priority::High, Priority::Medium, Priority::Low];
+    (1..=COUNT)
+        .map(|id| {
+            let mut task = Task::new(id, format!("任务 {}", id), priorities[id % 3]);
+            task.status = Status::ALL[id % Status::ALL.len()];
+            task.tags = vec![format!("tag{}", id % 100)];
+            task
+        })
+        .collect()
+}
+
+fn sample_todo(tasks: &[Task]) -> TodoList {
+    let mut todo = TodoList::new();
+    todo.tasks = tasks.iter().cloned().collect();
+    todo.next_id = COUNT + 1;
+    todo
+}
+
+fn lookup(c: &mut Criterion) {
+    let vec = sample_tasks();
+    let tasks: Tasks = vec.iter().cloned().collect();
+    let mut group = c.benchmark_group("按编号查找");
+    group.bench_function("Vec 线性查找", |b| {
+        b.iter(|| vec.iter().find(|task| task.id == black_box(COUNT / 2 + 7)))
+    });
+    group.bench_function("Tasks", |b| b.iter(|| tasks.get(black_box(COUNT / 2 + 7))));
+    group.finish();
+}
+
+fn by_priority(c: &mut Criterion) {
+    let vec = sample_tasks();
+    let tasks: Tasks = vec.iter().cloned().collect();
+    let mut group = c.benchmark_group("按优先级列出");
+    group.bench_function("Vec 复制后排序", |b| {
+        b.iter(|| {
+            let mut sorted = vec.clone();
+            sorted.sort_by_key(|task| (task.status.is_closed(), task.priority));
+            black_box(sorted.len())
+        })
+    });
+    group.bench_function("Tasks 优先级索引", |b| {
+        b.iter(|| {
+            let (mut sorted, closed): (Vec<&Task>, Vec<&Task>) = [Priority::High, Priority::Medium, Priority::Low]
+                .into_iter()
+                .flat_map(|priority| tasks.with_priority(priority))
+                .partition(|t| !t.status.is_closed());
+            sorted.extend(closed);
+            black_box(sorted.len())
+        })
+    });
+    group.finish();
+}
+
+fn stats(c: &mut Criterion) {
+    let vec = sample_tasks();
+    let tasks: Tasks = vec.iter().cloned().collect();
+    let open = |status: Status| !status.is_closed();
+    let mut group = c.benchmark_group("统计");
+    group.bench_function("Vec 多次遍历", |b| {
+        b.iter(|| {
+            let completed = vec.iter().filter(|t| t.status == Status::Done).count();
+            let high = vec.iter().filter(|t| t.priority == Priority::High && open(t.status)).count();
+            let medium = vec.iter().filter(|t| t.priority == Priority::Medium && open(t.status)).count();
+            let low = vec.iter().filter(|t| t.priority == Priority::Low && open(t.status)).count();
+            black_box((completed, high, medium, low))
+        })
+    });
+    group.bench_function("Tasks 计数", |b| {
+        b.iter(|| {
+            let completed = tasks.count_status(Status::Done);
+            let high = tasks.count(Priority::High, open);
+            let medium = tasks.count(Priority::Medium, open);
+            let low = tasks.count(Priority::Low, open);
+            black_box((completed, high, medium, low))
+        })
+    });
+    group.finish();
+}
+
+fn by_tag(c: &mut Criterion) {
+    let vec = sample_tasks();
+    let todo = sample_todo(&vec);
+    let filter = Filter::parse("+tag42 status:todo").unwrap();
+    let mut group = c.benchmark_group("按标签过滤");
+    group.bench_function("Vec 线性扫描", |b| {
+        b.iter(|| {
+            vec.iter()
+                .filter(|task| task.status == Status::Todo && task.tags.iter().any(|t| t.eq_ignore_ascii_case("tag42")))
+                .count()
+        })
+    });
+    group.bench_function("TodoList::filter_tasks", |b| b.iter(|| todo.filter_tasks(&filter).len()));
+    group.finish();
+}
+
+fn delete(c: &mut Criterion) {
+    let vec = sample_tasks();
+    let tasks: Tasks = vec.iter().cloned().collect();
+    let mut group = c.benchmark_group("删除后再加入");
+    group.sample_size(20);
+    group.bench_function("Vec", |b| {
+        let mut vec = vec.clone();
+        b.iter(|| {
+            let index = vec.iter().position(|task| task.id == COUNT / 2).unwrap();
+            let task = vec.remove(index);
+            vec.insert(index, task);
+        })
+    });
+    group.bench_function("Tasks", |b| {
+        let mut tasks = tasks.clone();
+        b.iter(|| {
+            let task = tasks.remove(COUNT / 2).unwrap();
+            tasks.insert(task);
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, lookup, by_priority, stats, by_tag, delete);
+criterion_main!(benches);
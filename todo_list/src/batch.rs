@@ -0,0 +1,211 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use clap::Parser;
+
+use crate::cli::{self, Command, ScriptLine};
+use crate::error::TodoError;
+use crate::storage::Store;
+use crate::todo::TodoList;
+
+// 按 shell 的规则拆分一行命令：空白分隔，支持单引号、双引号和反斜杠转义
+pub fn split_args(line: &str) -> Result<Vec<String>, TodoError> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                current.extend(chars.next());
+                in_word = true;
+            }
+            (Some(_), c) => current.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(TodoError::Script("引号没有闭合".to_string()));
+    }
+    if in_word {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+// 解析一行脚本，空行和 # 开头的注释返回 None
+fn parse_line(line: &str) -> Result<Option<Command>, TodoError> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let args = split_args(line)?;
+    let parsed = ScriptLine::try_parse_from(args).map_err(|e| {
+        let message = e.to_string();
+        let first = message.lines().next().unwrap_or_default();
+        TodoError::Script(first.trim_start_matches("error: ").to_string())
+    })?;
+    if matches!(parsed.command, Command::Batch { .. }) {
+        return Err(TodoError::Script("脚本中不能再执行 batch".to_string()));
+    }
+    Ok(Some(parsed.command))
+}
+
+// 逐行执行脚本，返回执行的命令数和失败的 (行号, 错误)；
+// 出错时默认停止，keep_going 时继续执行后面的命令
+fn run_script(
+    todo: &mut TodoList,
+    store: &mut Store,
+    script: &str,
+    keep_going: bool,
+) -> (usize, Vec<(usize, String)>) {
+    let mut total = 0;
+    let mut failures = Vec::new();
+    for (i, line) in script.lines().enumerate() {
+        // 解析失败的行也算一条失败的命令
+        let Some(command) = parse_line(line).transpose() else {
+            continue;
+        };
+        total += 1;
+        println!("\n▶ {}: {}", i + 1, line.trim());
+        let result = command.and_then(|command| {
+            if cli::run(command, todo, store)? {
+                store.save(todo)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            println!("🙅‍♂️{}", e);
+            failures.push((i + 1, e.to_string()));
+            if !keep_going {
+                break;
+            }
+        }
+    }
+    (total, failures)
+}
+
+// 批处理：执行脚本文件中的命令，每条修改都会保存，最后打印汇总
+pub fn run(todo: &mut TodoList, store: &mut Store, path: &Path, keep_going: bool) -> Result<(), TodoError> {
+    let script = if path == Path::new("-") {
+        let mut script = String::new();
+        io::stdin().read_to_string(&mut script)?;
+        script
+    } else {
+        fs::read_to_string(path)?
+    };
+
+    // 批处理不停下来询问，需要确认的命令按非交互方式执行
+    todo.config.interactive = false;
+    let (total, failures) = run_script(todo, store, &script, keep_going);
+
+    println!(
+        "\n📋 批处理完成：执行 {} 条命令，成功 {} 条，失败 {} 条",
+        total,
+        total - failures.len(),
+        failures.len()
+    );
+    for (line, error) in &failures {
+        println!("  第 {} 行：{}", line, error);
+    }
+    match failures.len() {
+        0 => Ok(()),
+        n => Err(TodoError::BatchFailed(n)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn args(line: &str) -> Vec<String> {
+        split_args(line).unwrap()
+    }
+
+    #[test]
+    fn split_handles_quotes_and_escapes() {
+        assert_eq!(args("  add   买牛奶 "), vec!["add", "买牛奶"]);
+        assert_eq!(args("add \"买 两盒 牛奶\" -t 'a b'"), vec!["add", "买 两盒 牛奶", "-t", "a b"]);
+        assert_eq!(args(r#"add "说 \"你好\"" it\'s"#), vec!["add", "说 \"你好\"", "it's"]);
+        // 单引号里的反斜杠和双引号按原样保留
+        assert_eq!(args(r#"add 'a\b "c"'"#), vec!["add", r#"a\b "c""#]);
+        assert_eq!(args(r#"add a\ b"#), vec!["add", "a b"]);
+        // 空引号也是一个参数，引号可以和普通字符连在一起
+        assert_eq!(args("add \"\" x\"y z\""), vec!["add", "", "xy z"]);
+        assert!(args("   ").is_empty());
+    }
+
+    #[test]
+    fn unterminated_quotes_are_errors() {
+        for line in ["add \"买牛奶", "add '买牛奶", r#"add "a\""#] {
+            assert!(matches!(split_args(line), Err(TodoError::Script(_))), "{}", line);
+        }
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        for line in ["", "   ", "# 注释", "   # 缩进的注释 \"没闭合"] {
+            assert!(parse_line(line).unwrap().is_none(), "{:?}", line);
+        }
+        assert!(matches!(parse_line(" done 3 "), Ok(Some(Command::Done { id: 3 }))));
+        assert!(matches!(parse_line("add \"买 牛奶\" -p high"), Ok(Some(Command::Add { .. }))));
+        assert!(matches!(parse_line("frobnicate"), Err(TodoError::Script(_))));
+        assert!(matches!(parse_line("done \"3"), Err(TodoError::Script(_))));
+        assert!(matches!(parse_line("batch x.txt"), Err(TodoError::Script(_))));
+    }
+
+    fn setup(dir: &Path) -> (TodoList, Store) {
+        let config = Config {
+            path: dir.join("config.toml"),
+            data_file: Some(dir.join("todo.json")),
+            interactive: false,
+            ..Config::default()
+        };
+        let store = Store::new(&config);
+        let mut todo = TodoList::new();
+        todo.config = config;
+        (todo, store)
+    }
+
+    const SCRIPT: &str = "# 准备\nadd 第一个\n\ndone 9\nadd \"第 二 个\"\nadd '没闭合\n";
+
+    #[test]
+    fn failing_lines_report_their_line_number() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut todo, mut store) = setup(dir.path());
+        let (total, failures) = run_script(&mut todo, &mut store, SCRIPT, false);
+        assert_eq!(total, 2);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, 4);
+        assert_eq!(todo.tasks.len(), 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let (mut todo, mut store) = setup(dir.path());
+        let (total, failures) = run_script(&mut todo, &mut store, SCRIPT, true);
+        assert_eq!(total, 4);
+        let lines: Vec<_> = failures.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![4, 6]);
+        assert!(failures[1].1.contains("引号没有闭合"));
+        let descriptions: Vec<_> = todo.tasks.iter().map(|t| t.description.as_str()).collect();
+        assert_eq!(descriptions, vec!["第一个", "第 二 个"]);
+        // 每条成功的修改都已经保存
+        assert_eq!(Store::new(&todo.config).load().unwrap().unwrap().tasks.len(), 2);
+    }
+}
@@ -0,0 +1,551 @@
+// 图形界面，和命令行共用配置和数据文件
+use std::cell::RefCell;
+use std::process;
+use std::rc::Rc;
+
+use slint::{ModelRc, SharedString, VecModel};
+
+use todo_list::config::{self, Config};
+use todo_list::date;
+use todo_list::dedupe::OnDuplicate;
+use todo_list::error::TodoError;
+use todo_list::filter::Filter;
+use todo_list::sort::SortSpec;
+use todo_list::storage::Store;
+use todo_list::task::{self, Priority, Status, Task, TaskEdit};
+use todo_list::todo::TodoList;
+
+slint::slint! {
+    import { Button, CheckBox, ComboBox, GroupBox, LineEdit, ListView, Palette, VerticalBox, HorizontalBox } from "std-widgets.slint";
+
+    export struct TaskRow {
+        id: int,
+        description: string,
+        // 0 高，1 中，2 低
+        priority: int,
+        status: string,
+        done: bool,
+        closed: bool,
+        due: string,
+        overdue: bool,
+        tags: string,
+        project: string,
+    }
+
+    export component TodoWindow inherits Window {
+        title: "待办事项";
+        preferred-width: 960px;
+        preferred-height: 600px;
+
+        in property <[TaskRow]> tasks;
+        in property <string> message;
+        // 统计
+        in property <int> total;
+        in property <int> done-count;
+        in property <int> pending;
+        in property <int> high;
+        in property <int> medium;
+        in property <int> low;
+        in property <int> overdue-count;
+        in-out property <string> search;
+
+        // 添加/编辑对话框，editing-id 为 0 表示新任务
+        in-out property <bool> dialog-open;
+        in-out property <int> editing-id;
+        in-out property <string> form-description;
+        in-out property <int> form-priority: 1;
+        in-out property <string> form-due;
+        in-out property <string> form-tags;
+        in-out property <string> form-project;
+        in property <string> form-error;
+        // 新建任务时发现的相似任务，0 表示没有
+        in-out property <int> similar-id;
+        in property <string> similar-text;
+
+        callback search-edited();
+        callback refresh();
+        callback toggle-done(int, bool);
+        callback open-edit(int);
+        callback save-task();
+        callback merge-similar();
+        callback add-anyway();
+        callback delete-task(int);
+        callback clear-done();
+
+        pure function priority-color(priority: int) -> color {
+            return priority == 0 ? #e53935 : priority == 1 ? #fbc02d : #43a047;
+        }
+
+        HorizontalBox {
+            VerticalBox {
+                HorizontalBox {
+                    padding: 0;
+                    LineEdit {
+                        placeholder-text: "搜索，例如 报告、priority:high +work";
+                        text <=> root.search;
+                        edited => { root.search-edited(); }
+                    }
+                    Button { text: "➕ 新任务"; clicked => { root.open-edit(0); } }
+                    Button { text: "🔄 刷新"; clicked => { root.refresh(); } }
+                }
+
+                ListView {
+                    for task in root.tasks : Rectangle {
+                        height: 40px;
+                        HorizontalLayout {
+                            spacing: 8px;
+                            padding-left: 4px;
+                            padding-right: 4px;
+                            CheckBox {
+                                checked: task.done;
+                                enabled: !task.closed || task.done;
+                                toggled => { root.toggle-done(task.id, self.checked); }
+                            }
+                            Rectangle {
+                                width: 6px;
+                                height: 24px;
+                                y: (parent.height - self.height) / 2;
+                                border-radius: 3px;
+                                background: root.priority-color(task.priority);
+                            }
+                            Text {
+                                width: 40px;
+                                text: "#" + task.id;
+                                vertical-alignment: center;
+                                color: Palette.foreground.transparentize(40%);
+                            }
+                            Text {
+                                horizontal-stretch: 1;
+                                text: task.description;
+                                vertical-alignment: center;
+                                overflow: elide;
+                                color: task.closed ? Palette.foreground.transparentize(50%) : Palette.foreground;
+                            }
+                            Text {
+                                text: task.project;
+                                vertical-alignment: center;
+                                color: Palette.foreground.transparentize(30%);
+                            }
+                            Text {
+                                text: task.tags;
+                                vertical-alignment: center;
+                                color: #1e88e5;
+                            }
+                            Text {
+                                width: 110px;
+                                text: task.due;
+                                vertical-alignment: center;
+                                color: task.overdue ? #e53935 : Palette.foreground;
+                            }
+                            Text {
+                                width: 50px;
+                                text: task.status;
+                                vertical-alignment: center;
+                            }
+                            Button { text: "编辑"; clicked => { root.open-edit(task.id); } }
+                            Button { text: "删除"; clicked => { root.delete-task(task.id); } }
+                        }
+                    }
+                }
+
+                Text { text: root.message; }
+            }
+
+            GroupBox {
+                title: "📊 统计";
+                width: 180px;
+                VerticalLayout {
+                    spacing: 6px;
+                    alignment: start;
+                    Text { text: "总任务数：" + root.total; }
+                    Text { text: "已完成：" + root.done-count + " ✅"; }
+                    Text { text: "待完成：" + root.pending + " ⏳"; }
+                    Text { text: "已逾期：" + root.overdue-count; color: root.overdue-count > 0 ? #e53935 : Palette.foreground; }
+                    Text { text: "高优先级待办：" + root.high; color: root.priority-color(0); }
+                    Text { text: "中优先级待办：" + root.medium; color: root.priority-color(1); }
+                    Text { text: "低优先级待办：" + root.low; color: root.priority-color(2); }
+                    Button { text: "🗑️ 清除已完成"; clicked => { root.clear-done(); } }
+                }
+            }
+        }
+
+        if root.dialog-open : Rectangle {
+            background: #00000080;
+            // 挡住对话框后面的点击
+            TouchArea { }
+            Rectangle {
+                width: 420px;
+                height: 420px;
+                border-radius: 8px;
+                background: Palette.background;
+                VerticalBox {
+                    Text {
+                        text: root.editing-id == 0 ? "新建任务" : "编辑任务 #" + root.editing-id;
+                        font-size: 16px;
+                    }
+                    LineEdit { placeholder-text: "描述"; text <=> root.form-description; }
+                    ComboBox { model: ["🔴 高", "🟡 中", "🟢 低"]; current-index <=> root.form-priority; }
+                    LineEdit { placeholder-text: "截止时间，例如 today、+3d、2024-05-01 18:00"; text <=> root.form-due; }
+                    LineEdit { placeholder-text: "标签，用逗号或空格分隔"; text <=> root.form-tags; }
+                    LineEdit { placeholder-text: "项目，例如 工作/周报"; text <=> root.form-project; }
+                    Text { text: root.form-error; color: #e53935; }
+                    if root.similar-id != 0 : VerticalLayout {
+                        spacing: 4px;
+                        Text { text: root.similar-text; color: #fb8c00; wrap: word-wrap; }
+                        HorizontalBox {
+                            padding: 0;
+                            alignment: end;
+                            Button { text: "并入任务 #" + root.similar-id; clicked => { root.merge-similar(); } }
+                            Button { text: "仍然添加"; clicked => { root.add-anyway(); } }
+                        }
+                    }
+                    HorizontalBox {
+                        padding: 0;
+                        alignment: end;
+                        Button { text: "取消"; clicked => { root.dialog-open = false; } }
+                        Button { text: "保存"; primary: true; clicked => { root.save-task(); } }
+                    }
+                }
+            }
+        }
+    }
+}
+
+const PRIORITIES: [Priority; 3] = [Priority::High, Priority::Medium, Priority::Low];
+
+fn priority_index(priority: Priority) -> i32 {
+    PRIORITIES.iter().position(|&p| p == priority).unwrap_or(1) as i32
+}
+
+// 界面背后的任务列表和数据文件
+struct App {
+    todo: TodoList,
+    store: Store,
+}
+
+impl App {
+    fn load() -> Result<App, TodoError> {
+        let config = Config::load(&config::default_config_path())?;
+        let mut store = Store::new(&config);
+        let mut todo = store.load()?.unwrap_or_else(TodoList::new);
+        todo.config = config;
+        Ok(App { todo, store })
+    }
+
+    // 重新读取数据文件，拿到命令行或其他窗口的修改
+    fn reload(&mut self) -> Result<(), TodoError> {
+        if let Some(mut latest) = self.store.load()? {
+            latest.config = std::mem::take(&mut self.todo.config);
+            self.todo = latest;
+        }
+        Ok(())
+    }
+
+    // 在最新的数据上修改并保存
+    fn change(&mut self, f: impl FnOnce(&mut TodoList) -> Result<(), TodoError>) -> Result<(), TodoError> {
+        self.reload()?;
+        f(&mut self.todo)?;
+        self.store.save(&mut self.todo)
+    }
+}
+
+fn task_row(todo: &TodoList, task: &Task) -> TaskRow {
+    let tags: Vec<String> = task.tags.iter().map(|tag| format!("#{}", tag)).collect();
+    TaskRow {
+        id: task.id as i32,
+        description: task.description.as_str().into(),
+        priority: priority_index(task.priority),
+        status: task.status.to_string().into(),
+        done: task.status == Status::Done,
+        closed: task.status.is_closed(),
+        due: task.due.map(|due| todo.config.format_due(due)).unwrap_or_default().into(),
+        overdue: task.due.is_some_and(|due| due < date::now()) && !task.status.is_closed(),
+        tags: tags.join(" ").into(),
+        project: task.project.as_deref().unwrap_or_default().into(),
+    }
+}
+
+// 按搜索条件刷新任务列表和统计
+fn refresh(window: &TodoWindow, todo: &TodoList) {
+    let mut tasks = match Filter::parse(&window.get_search()) {
+        Ok(filter) => todo.filter_tasks(&filter),
+        Err(e) => {
+            window.set_message(format!("🙅‍♂️{}", e).into());
+            Vec::new()
+        }
+    };
+    if let Ok(sort) = SortSpec::parse("priority,due") {
+        sort.sort(todo, &mut tasks);
+    }
+    let rows: Vec<TaskRow> = tasks.iter().map(|task| task_row(todo, task)).collect();
+    window.set_tasks(ModelRc::new(VecModel::from(rows)));
+
+    let total = todo.tasks.len();
+    let done = todo.tasks.count_status(Status::Done);
+    let open = |status: Status| !status.is_closed();
+    let now = date::now();
+    window.set_total(total as i32);
+    window.set_done_count(done as i32);
+    window.set_pending((total - done - todo.tasks.count_status(Status::Cancelled)) as i32);
+    window.set_high(todo.tasks.count(Priority::High, open) as i32);
+    window.set_medium(todo.tasks.count(Priority::Medium, open) as i32);
+    window.set_low(todo.tasks.count(Priority::Low, open) as i32);
+    let overdue = todo
+        .tasks
+        .iter()
+        .filter(|task| !task.status.is_closed() && task.due.is_some_and(|due| due < now))
+        .count();
+    window.set_overdue_count(overdue as i32);
+}
+
+// 执行一次修改，成功时显示提示，失败时显示错误；之后都刷新界面
+fn apply(
+    window: &TodoWindow,
+    app: &RefCell<App>,
+    f: impl FnOnce(&mut TodoList) -> Result<String, TodoError>,
+) -> bool {
+    let mut app = app.borrow_mut();
+    let mut message = String::new();
+    let result = app.change(|todo| {
+        message = f(todo)?;
+        Ok(())
+    });
+    let ok = match result {
+        Ok(()) => {
+            window.set_message(message.into());
+            true
+        }
+        Err(e) => {
+            window.set_message(format!("🙅‍♂️{}", e).into());
+            false
+        }
+    };
+    refresh(window, &app.todo);
+    ok
+}
+
+// 读取对话框中的内容，描述已确认不为空
+fn read_form(window: &TodoWindow) -> Result<(String, TaskEdit), TodoError> {
+    let description = window.get_form_description().trim().to_string();
+    let due = window.get_form_due();
+    let due = match due.trim() {
+        "" => None,
+        due => Some(date::parse_due(due)?),
+    };
+    let project = window.get_form_project().trim().to_string();
+    let edit = TaskEdit {
+        description: Some(description.clone()),
+        priority: PRIORITIES.get(window.get_form_priority() as usize).copied(),
+        due: Some(due),
+        tags: Some(task::parse_tags(&window.get_form_tags())),
+        project: Some((!project.is_empty()).then_some(project)),
+        ..TaskEdit::default()
+    };
+    Ok((description, edit))
+}
+
+// 保存对话框。新建任务时先找相似的未完成任务，找到时让用户选择并入还是仍然添加
+fn save_form(window: &TodoWindow, app: &RefCell<App>, on_duplicate: OnDuplicate) {
+    if window.get_form_description().trim().is_empty() {
+        window.set_form_error("🙅任务描述不能为空！".into());
+        return;
+    }
+    let (description, edit) = match read_form(window) {
+        Ok(form) => form,
+        Err(e) => {
+            window.set_form_error(format!("🙅‍♂️{}", e).into());
+            return;
+        }
+    };
+    let id = window.get_editing_id() as usize;
+    if id == 0 && on_duplicate == OnDuplicate::Ask {
+        let mut app = app.borrow_mut();
+        if let Err(e) = app.reload() {
+            window.set_form_error(format!("🙅‍♂️{}", e).into());
+            return;
+        }
+        let similar = match app.todo.config.dedupe.check_on_add {
+            true => app.todo.similar_tasks(&description),
+            false => Vec::new(),
+        };
+        if let Some((task, score)) = similar.first() {
+            window.set_similar_id(task.id as i32);
+            window.set_similar_text(
+                format!(
+                    "⚠️ 发现 {} 个相似的未完成任务，最相似的是 #{} {}（相似度 {:.0}%）",
+                    similar.len(),
+                    task.id,
+                    task.description,
+                    score * 100.0
+                )
+                .into(),
+            );
+            return;
+        }
+    }
+    let keep = window.get_similar_id() as usize;
+    let saved = apply(window, app, |todo| {
+        let priority = edit.priority.unwrap_or(todo.config.default_priority);
+        match (id, on_duplicate) {
+            (0, OnDuplicate::Merge) => {
+                let task = todo.new_task(description, priority.to_english(), edit)?;
+                todo.merge_new_task(keep, &task)?;
+                Ok(format!("🔗 已并入任务 #{}", keep))
+            }
+            (0, _) => {
+                let id = todo.add_task_with(description, priority.to_english(), edit)?;
+                Ok(format!("👌 已添加任务 #{}", id))
+            }
+            _ => {
+                todo.edit_task(id, edit)?;
+                Ok(format!("✏️ 已修改任务 #{}", id))
+            }
+        }
+    });
+    if saved {
+        window.set_dialog_open(false);
+    } else {
+        window.set_form_error(window.get_message());
+    }
+}
+
+fn open_edit(window: &TodoWindow, todo: &TodoList, id: i32) {
+    let task = todo.get(id as usize);
+    let text = |s: String| SharedString::from(s);
+    window.set_editing_id(task.map_or(0, |task| task.id as i32));
+    window.set_form_description(text(task.map(|task| task.description.clone()).unwrap_or_default()));
+    window.set_form_priority(priority_index(task.map_or(todo.config.default_priority, |task| task.priority)));
+    window.set_form_due(text(
+        task.and_then(|task| task.due)
+            .map(|due| due.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default(),
+    ));
+    window.set_form_tags(text(task.map(|task| task.tags.join(", ")).unwrap_or_default()));
+    window.set_form_project(text(task.and_then(|task| task.project.clone()).unwrap_or_default()));
+    window.set_form_error(SharedString::new());
+    window.set_similar_id(0);
+    window.set_dialog_open(true);
+}
+
+fn run() -> Result<(), TodoError> {
+    let app = Rc::new(RefCell::new(App::load()?));
+    let window = TodoWindow::new().map_err(|e| TodoError::Gui(e.to_string()))?;
+    refresh(&window, &app.borrow().todo);
+
+    {
+        let weak = window.as_weak();
+        let app = app.clone();
+        window.on_search_edited(move || {
+            if let Some(window) = weak.upgrade() {
+                window.set_message(SharedString::new());
+                refresh(&window, &app.borrow().todo);
+            }
+        });
+    }
+
+    {
+        let weak = window.as_weak();
+        let app = app.clone();
+        window.on_refresh(move || {
+            if let Some(window) = weak.upgrade() {
+                let mut app = app.borrow_mut();
+                match app.reload() {
+                    Ok(()) => window.set_message("🔄 已刷新".into()),
+                    Err(e) => window.set_message(format!("🙅‍♂️{}", e).into()),
+                }
+                refresh(&window, &app.todo);
+            }
+        });
+    }
+
+    {
+        let weak = window.as_weak();
+        let app = app.clone();
+        window.on_toggle_done(move |id, checked| {
+            if let Some(window) = weak.upgrade() {
+                apply(&window, &app, |todo| {
+                    let id = id as usize;
+                    // 取消勾选时重新打开为待办
+                    let status = if checked { Status::Done } else { Status::Todo };
+                    todo.set_status(id, status)?;
+                    Ok(format!("{} 任务 #{} 已标记为「{}」", status.to_emoji(), id, status.to_string()))
+                });
+            }
+        });
+    }
+
+    {
+        let weak = window.as_weak();
+        let app = app.clone();
+        window.on_open_edit(move |id| {
+            if let Some(window) = weak.upgrade() {
+                open_edit(&window, &app.borrow().todo, id);
+            }
+        });
+    }
+
+    {
+        let weak = window.as_weak();
+        let app = app.clone();
+        window.on_save_task(move || {
+            if let Some(window) = weak.upgrade() {
+                save_form(&window, &app, OnDuplicate::Ask);
+            }
+        });
+    }
+
+    {
+        let weak = window.as_weak();
+        let app = app.clone();
+        window.on_merge_similar(move || {
+            if let Some(window) = weak.upgrade() {
+                save_form(&window, &app, OnDuplicate::Merge);
+            }
+        });
+    }
+
+    {
+        let weak = window.as_weak();
+        let app = app.clone();
+        window.on_add_anyway(move || {
+            if let Some(window) = weak.upgrade() {
+                save_form(&window, &app, OnDuplicate::Add);
+            }
+        });
+    }
+
+    {
+        let weak = window.as_weak();
+        let app = app.clone();
+        window.on_delete_task(move |id| {
+            if let Some(window) = weak.upgrade() {
+                apply(&window, &app, |todo| {
+                    todo.delete_task(id as usize)?;
+                    Ok(format!("🗑️ 已删除任务 #{}", id))
+                });
+            }
+        });
+    }
+
+    {
+        let weak = window.as_weak();
+        let app = app.clone();
+        window.on_clear_done(move || {
+            if let Some(window) = weak.upgrade() {
+                apply(&window, &app, |todo| {
+                    todo.delete_complete_task()?;
+                    Ok("🗑️ 已清除已完成的任务".to_string())
+                });
+            }
+        });
+    }
+
+    window.run().map_err(|e| TodoError::Gui(e.to_string()))
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("🙅‍♂️{}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,235 @@
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::config::Lang;
+use crate::date;
+use crate::error::TodoError;
+use crate::sort::SortSpec;
+use crate::task::{Priority, Task};
+use crate::todo::TodoList;
+
+// 解析月份，例如 2024-05
+pub fn parse_month(s: &str) -> Result<NaiveDate, TodoError> {
+    NaiveDate::parse_from_str(&format!("{}-01", s.trim()), "%Y-%m-%d")
+        .map_err(|_| TodoError::InvalidDate(s.to_string()))
+}
+
+// 某天所在月份的第一天
+pub fn month_start(day: NaiveDate) -> NaiveDate {
+    day.with_day(1).unwrap_or(day)
+}
+
+// 翻页：偏移若干个月
+pub fn shift_month(month: NaiveDate, offset: i32) -> NaiveDate {
+    let months = Months::new(offset.unsigned_abs());
+    let shifted = if offset >= 0 {
+        month.checked_add_months(months)
+    } else {
+        month.checked_sub_months(months)
+    };
+    shifted.unwrap_or(month)
+}
+
+// 月历的格子：一行一周，从周一开始，不属于本月的格子为 None
+pub fn month_grid(month: NaiveDate) -> Vec<[Option<NaiveDate>; 7]> {
+    let first = month_start(month);
+    let mut weeks = Vec::new();
+    let mut week = [None; 7];
+    let mut date = first;
+    while date.month() == first.month() && date.year() == first.year() {
+        let weekday = date.weekday().num_days_from_monday() as usize;
+        week[weekday] = Some(date);
+        if weekday == 6 {
+            weeks.push(week);
+            week = [None; 7];
+        }
+        match date.succ_opt() {
+            Some(next) => date = next,
+            None => break,
+        }
+    }
+    if week.iter().any(Option::is_some) {
+        weeks.push(week);
+    }
+    weeks
+}
+
+// --day 的日期，支持 today、+3d 等写法
+pub fn parse_day(s: &str) -> Result<NaiveDate, TodoError> {
+    Ok(date::parse_due(s)?.date())
+}
+
+impl TodoList {
+    // 某天截止的任务
+    fn tasks_due_on(&self, day: NaiveDate) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|task| task.due.is_some_and(|due| due.date() == day))
+            .collect()
+    }
+
+    // 月历上标出的任务：某天截止且没有结束的
+    fn open_tasks_due_on(&self, day: NaiveDate) -> Vec<&Task> {
+        self.tasks_due_on(day)
+            .into_iter()
+            .filter(|task| !task.status.is_closed())
+            .collect()
+    }
+
+    // 月历：一行一周，从周一开始；有任务截止的日期按最高优先级着色并加上 •，今天用 [] 标出
+    pub fn show_calendar(&self, month: NaiveDate) {
+        let cfg = &self.config;
+        let first = month_start(month);
+        let today = date::today();
+
+        let title = match cfg.view_language {
+            Lang::Zh => first.format("%Y年%m月").to_string(),
+            Lang::En => first.format("%B %Y").to_string(),
+        };
+        println!("\n{}{}", cfg.icon("📅 "), title);
+        let weekdays = cfg.tr("一 二 三 四 五 六 日", "Mo Tu We Th Fr Sa Su");
+        // 星期名都占两格，和日期的两位数字对齐
+        let header: Vec<String> = weekdays.split(' ').map(|d| format!(" {}  ", d)).collect();
+        println!("{}", header.concat().trim_end());
+
+        let mut due_in_month = 0;
+        for week in month_grid(first) {
+            let mut line = String::new();
+            for date in week {
+                let Some(date) = date else {
+                    line.push_str("     ");
+                    continue;
+                };
+                let tasks = self.open_tasks_due_on(date);
+                due_in_month += tasks.len();
+                let (left, right) = if date == today { ('[', ']') } else { (' ', ' ') };
+                let mark = if tasks.is_empty() { ' ' } else { '•' };
+                let cell = format!("{}{:>2}{}{}", left, date.day(), right, mark);
+                // 优先级的顺序是高、中、低，最小的就是最高优先级
+                let cell = match tasks.iter().map(|task| task.priority).min() {
+                    Some(priority) => cfg.paint(&cell, cfg.priority_color(priority)),
+                    None => cell,
+                };
+                line.push_str(&cell);
+            }
+            println!("{}", line.trim_end());
+        }
+
+        match cfg.view_language {
+            Lang::Zh => println!("\n本月有 {} 个未结束的任务截止", due_in_month),
+            Lang::En => println!("\nOpen tasks due this month: {}", due_in_month),
+        }
+        let legend: Vec<String> = [Priority::High, Priority::Medium, Priority::Low]
+            .into_iter()
+            .map(|p| cfg.paint(&format!("• {}", cfg.priority_name(p)), cfg.priority_color(p)))
+            .collect();
+        println!("{}", legend.join("  "));
+    }
+
+    // 列出某天截止的任务
+    pub fn list_day(&self, day: NaiveDate) -> Result<(), TodoError> {
+        let tasks = self.tasks_due_on(day);
+        let cfg = &self.config;
+        if tasks.is_empty() {
+            println!(
+                "{}{} {}",
+                cfg.icon("📅 "),
+                day.format(&cfg.date_format),
+                cfg.tr("没有截止的任务", "has no tasks due")
+            );
+            return Ok(());
+        }
+        let title = format!(
+            "{}{} {}",
+            cfg.icon("📅 "),
+            day.format(&cfg.date_format),
+            cfg.tr("截止的任务：", "tasks due:")
+        );
+        self.print_tasks(&title, tasks, &SortSpec::parse("priority,due")?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Status;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn days_in(grid: &[[Option<NaiveDate>; 7]]) -> Vec<NaiveDate> {
+        grid.iter().flatten().flatten().copied().collect()
+    }
+
+    #[test]
+    fn grid_starts_on_the_right_weekday() {
+        // 2024 年 5 月 1 日是周三
+        let grid = month_grid(day("2024-05-17"));
+        assert_eq!(grid.len(), 5);
+        assert_eq!(grid[0][..2], [None, None]);
+        assert_eq!(grid[0][2], Some(day("2024-05-01")));
+        assert_eq!(grid[0][6], Some(day("2024-05-05")));
+        assert_eq!(grid[4][4], Some(day("2024-05-31")));
+        assert_eq!(grid[4][5..], [None, None]);
+        assert_eq!(days_in(&grid).len(), 31);
+
+        // 2021 年 2 月从周一开始，正好四周
+        let grid = month_grid(day("2021-02-01"));
+        assert_eq!(grid.len(), 4);
+        assert_eq!(grid[0][0], Some(day("2021-02-01")));
+        assert_eq!(grid[3][6], Some(day("2021-02-28")));
+    }
+
+    #[test]
+    fn grid_has_the_month_length() {
+        assert_eq!(days_in(&month_grid(day("2024-02-01"))).len(), 29);
+        assert_eq!(days_in(&month_grid(day("2023-02-01"))).len(), 28);
+        assert_eq!(days_in(&month_grid(day("1900-02-01"))).len(), 28);
+        assert_eq!(days_in(&month_grid(day("2000-02-01"))).len(), 29);
+        assert_eq!(days_in(&month_grid(day("2024-04-01"))).len(), 30);
+        assert_eq!(days_in(&month_grid(day("2024-12-01"))).len(), 31);
+        // 每个格子都在自己的星期列上
+        for week in month_grid(day("2024-02-01")) {
+            for (column, date) in week.iter().enumerate() {
+                if let Some(date) = date {
+                    assert_eq!(date.weekday().num_days_from_monday() as usize, column);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn months_parse_and_shift() {
+        assert_eq!(parse_month("2024-05").unwrap(), day("2024-05-01"));
+        assert!(parse_month("2024-13").is_err());
+        assert_eq!(shift_month(day("2024-01-01"), -1), day("2023-12-01"));
+        assert_eq!(shift_month(day("2024-12-01"), 2), day("2025-02-01"));
+    }
+
+    #[test]
+    fn tasks_are_placed_on_their_due_day() {
+        let mut todo = TodoList::new();
+        for (description, due) in [("a", "2024-02-29 09:00"), ("b", "2024-02-29"), ("c", "2024-03-01")] {
+            let id = todo.add_task(description.to_string(), "medium").unwrap();
+            todo.tasks.update(id, |task| task.due = Some(date::parse_due(due).unwrap()));
+        }
+        todo.add_task("no due".to_string(), "medium").unwrap();
+        todo.set_status(2, Status::Done).unwrap();
+
+        let ids = |tasks: Vec<&Task>| tasks.iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(todo.tasks_due_on(day("2024-02-29"))), vec![1, 2]);
+        // 月历上不标出已结束的任务
+        assert_eq!(ids(todo.open_tasks_due_on(day("2024-02-29"))), vec![1]);
+        assert_eq!(ids(todo.tasks_due_on(day("2024-03-01"))), vec![3]);
+        assert!(todo.tasks_due_on(day("2024-02-28")).is_empty());
+    }
+
+    #[test]
+    fn day_option_accepts_dates_and_relative_days() {
+        assert_eq!(parse_day("2024-05-01").unwrap(), day("2024-05-01"));
+        assert_eq!(parse_day("today").unwrap(), date::today());
+        assert_eq!(parse_day("+1d").unwrap(), date::today().succ_opt().unwrap());
+        assert!(parse_day("someday").is_err());
+    }
+}
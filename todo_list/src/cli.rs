@@ -0,0 +1,668 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use clap::{Parser, Subcommand};
+
+use crate::batch;
+use crate::calendar;
+use crate::config::{self, ColorMode, Config, Lang};
+use crate::date;
+use crate::dedupe::{self, OnDuplicate};
+use crate::editor;
+use crate::error::TodoError;
+use crate::estimate::{self, Estimate};
+use crate::filter::Filter;
+use crate::markdown;
+use crate::pomodoro;
+use crate::remind;
+use crate::report;
+use crate::sort::SortSpec;
+use crate::storage::Store;
+use crate::sync::{self, Resolve};
+use crate::task::{self, Priority, Status, TaskEdit};
+use crate::taskwarrior;
+use crate::template;
+use crate::todo::TodoList;
+
+// 命令行参数，不带子命令时进入交互式菜单
+#[derive(Debug, Parser)]
+#[command(name = "todo_list", version, about = "🎯 Rust任务管理器")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// 配置文件，默认为 $XDG_CONFIG_HOME/todo_list/config.toml
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    /// 数据文件，覆盖配置中的 data_file
+    #[arg(short, long, global = true)]
+    pub file: Option<PathBuf>,
+    /// 新任务的默认优先级
+    #[arg(long, global = true)]
+    pub default_priority: Option<String>,
+    /// 不显示 emoji
+    #[arg(long, global = true)]
+    pub no_emoji: bool,
+    /// 日期格式，例如 %Y/%m/%d
+    #[arg(long, global = true)]
+    pub date_format: Option<String>,
+    /// 视图语言：zh/en，只影响列表、统计、日历和报告等视图，提示和错误信息固定为中文
+    #[arg(long = "view-lang", alias = "lang", global = true)]
+    pub view_lang: Option<String>,
+    /// 颜色：auto/always/never
+    #[arg(long, global = true)]
+    pub color: Option<String>,
+    /// 纯文本输出：不带 emoji 和颜色，适合重定向到文件
+    #[arg(long, global = true)]
+    pub plain: bool,
+}
+
+// 批处理脚本中的一行，和命令行的子命令相同
+#[derive(Debug, Parser)]
+#[command(name = "todo_list", no_binary_name = true)]
+pub struct ScriptLine {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+impl Cli {
+    // 命令行参数覆盖配置文件
+    pub fn apply(&self, config: &mut Config) -> Result<(), TodoError> {
+        if let Some(file) = &self.file {
+            config.data_file = Some(file.clone());
+        }
+        if let Some(priority) = &self.default_priority {
+            config.default_priority = Priority::from_str(priority)?;
+        }
+        if self.no_emoji {
+            config.emoji = false;
+        }
+        if let Some(format) = &self.date_format {
+            config::check_date_format(format)?;
+            config.date_format = format.clone();
+        }
+        if let Some(lang) = &self.view_lang {
+            config.view_language = Lang::from_str(lang)?;
+        }
+        if let Some(color) = &self.color {
+            config.colors.mode = ColorMode::from_str(color)?;
+        }
+        if self.plain {
+            config.emoji = false;
+            config.colors.mode = ColorMode::Never;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 添加任务
+    Add {
+        description: String,
+        /// 优先级：high/medium/low 或 h/m/l 或 1/2/3，默认使用配置中的 default_priority
+        #[arg(short, long)]
+        priority: Option<String>,
+        /// 截止时间：2024-05-01、2024-05-01 18:00、today、tomorrow 或 +3d
+        #[arg(short, long)]
+        due: Option<String>,
+        /// 标签，逗号分隔
+        #[arg(short, long)]
+        tags: Option<String>,
+        /// 依赖的任务编号，逗号分隔
+        #[arg(long)]
+        depends: Option<String>,
+        /// 所属项目，可以用 / 分出层级
+        #[arg(short = 'P', long)]
+        project: Option<String>,
+        /// 父任务编号，新任务作为它的子任务
+        #[arg(long)]
+        parent: Option<String>,
+        /// 工作量预估：90、90m、1.5h 或故事点 3pt
+        #[arg(short, long)]
+        estimate: Option<String>,
+        /// 有相似的未完成任务时不询问，直接并入最相似的那个
+        #[arg(long, conflicts_with = "force")]
+        merge: bool,
+        /// 不检查重复，直接添加
+        #[arg(long)]
+        force: bool,
+    },
+    /// 列出任务
+    List {
+        /// 排序方式，例如 priority,-due,created，前缀 - 表示倒序
+        #[arg(short, long, allow_hyphen_values = true)]
+        sort: Option<String>,
+    },
+    /// 完成任务
+    Done { id: usize },
+    /// 删除任务
+    Delete { id: usize },
+    /// 删除所有已完成或已取消的任务
+    Clear,
+    /// 修改任务状态：todo/in-progress/waiting/blocked/done/cancelled
+    Status { id: usize, status: String },
+    /// 编辑任务，传入 - 清除截止时间、标签、依赖、项目、父任务或预估
+    Edit {
+        id: usize,
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(short, long)]
+        priority: Option<String>,
+        #[arg(short, long)]
+        due: Option<String>,
+        #[arg(short, long)]
+        tags: Option<String>,
+        #[arg(long)]
+        depends: Option<String>,
+        #[arg(short = 'P', long)]
+        project: Option<String>,
+        #[arg(long)]
+        parent: Option<String>,
+        #[arg(short, long)]
+        estimate: Option<String>,
+    },
+    /// 查看任务详情
+    Show { id: usize },
+    /// 编辑任务备注，不带参数时在 $EDITOR 中编辑
+    Note {
+        id: usize,
+        /// 直接设置备注内容
+        #[arg(long, conflicts_with = "append")]
+        set: Option<String>,
+        /// 在备注末尾追加一行
+        #[arg(long)]
+        append: Option<String>,
+    },
+    /// 搜索任务（描述和备注）
+    Search {
+        keyword: String,
+        #[arg(short, long, allow_hyphen_values = true)]
+        sort: Option<String>,
+    },
+    /// 按查询条件筛选任务，例如 priority:high status:pending due:today +work 关键词
+    Filter {
+        #[arg(short, long, allow_hyphen_values = true)]
+        sort: Option<String>,
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        query: Vec<String>,
+    },
+    /// 列出保存的视图和各自的任务数
+    Views,
+    /// 查看保存的视图
+    View {
+        name: String,
+        #[arg(short, long, allow_hyphen_values = true)]
+        sort: Option<String>,
+    },
+    /// 保存视图，同名视图会被覆盖
+    SaveView {
+        name: String,
+        /// 视图默认的排序方式
+        #[arg(short, long, allow_hyphen_values = true)]
+        sort: Option<String>,
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        query: Vec<String>,
+    },
+    /// 删除保存的视图
+    DeleteView { name: String },
+    /// 查看剩余工作量，按优先级和项目汇总
+    Effort,
+    /// 规划今天：在时间预算内挑选要做的任务
+    Plan {
+        /// 时间预算，例如 4h、90m，默认使用配置中的 plan.budget
+        budget: Option<String>,
+    },
+    /// 提醒模式：在截止前按配置的时间提醒，持续运行
+    Remind {
+        /// 只检查一次就退出，适合放在 cron 中
+        #[arg(long)]
+        once: bool,
+    },
+    /// 列出任务模板
+    Templates,
+    /// 用模板创建一组任务
+    Instantiate {
+        name: String,
+        /// 模板变量，例如 --set version=1.2，可以指定多次
+        #[arg(short = 's', long = "set", value_name = "名称=值")]
+        vars: Vec<String>,
+        /// 相对截止时间的起算日期，默认今天
+        #[arg(long)]
+        date: Option<String>,
+        /// 只预览将要创建的任务，不保存
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// 导入 Markdown 清单中的 - [ ] / - [x] 条目
+    ImportMd {
+        markdown: PathBuf,
+        /// 只预览将要导入的任务，不保存
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+        /// 标题的用法：project 作为项目（默认），tag 作为标签
+        #[arg(long, default_value = "project")]
+        headings: String,
+    },
+    /// 导入 Taskwarrior 导出的 JSON（task export），- 表示从标准输入读取
+    ImportTw { json: PathBuf },
+    /// 导出为 Taskwarrior 的 JSON 格式，可用 task import 导入
+    ExportTw {
+        /// 输出文件，默认输出到标准输出
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// 和另一个数据文件双向同步，例如共享目录中的副本，两边都改过的任务会询问保留哪一边
+    Sync {
+        remote: PathBuf,
+        /// 冲突时都保留本地的版本
+        #[arg(long, conflicts_with = "theirs")]
+        ours: bool,
+        /// 冲突时都保留远端的版本
+        #[arg(long)]
+        theirs: bool,
+        /// 只预览，不修改两边的文件
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// 生成报告：一段时间内完成、新增、逾期和延续的任务，按优先级和项目分组
+    Report {
+        /// 按周生成，不带值为本周，1 为上周，依此类推；不指定日期范围时默认为本周
+        #[arg(long, num_args = 0..=1, default_missing_value = "0", conflicts_with_all = ["from", "to"])]
+        week: Option<u32>,
+        /// 开始日期，例如 2024-05-01
+        #[arg(long)]
+        from: Option<String>,
+        /// 结束日期（含），默认为今天
+        #[arg(long)]
+        to: Option<String>,
+        /// 报告格式：markdown/html，默认按输出文件的扩展名判断
+        #[arg(long)]
+        format: Option<String>,
+        /// 输出文件，默认输出到标准输出
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// 查找相似的未完成任务，逐组询问是否合并，每组保留最早的任务
+    Dedupe {
+        /// 不询问，全部合并
+        #[arg(short, long, conflicts_with = "dry_run")]
+        yes: bool,
+        /// 只列出重复的任务，不合并
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// 批处理：逐行执行脚本文件中的命令，- 表示从标准输入读取
+    Batch {
+        script: PathBuf,
+        /// 出错后继续执行后面的命令
+        #[arg(short, long)]
+        keep_going: bool,
+    },
+    /// 查看统计
+    Stats,
+    /// 看板视图
+    Kanban {
+        #[arg(short, long, allow_hyphen_values = true)]
+        sort: Option<String>,
+    },
+    /// 按紧急度查看任务
+    Urgency,
+    /// 四象限视图
+    Matrix,
+    /// 月历视图，标出有任务截止的日期
+    Calendar {
+        /// 月份，例如 2024-05，默认为本月
+        month: Option<String>,
+        /// 列出某天截止的任务，例如 2024-05-01 或 today
+        #[arg(short, long)]
+        day: Option<String>,
+    },
+    /// 显示生效的配置
+    Config,
+    /// 查看数据文件的 git 历史（需要开启 storage.git）
+    Log {
+        /// 最多显示的条数
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// 把任务列表恢复到 git 历史中的某个版本
+    Restore { rev: String },
+    /// 设置或修改数据文件的加密口令
+    Passwd,
+    /// 把日志写成快照并清空日志（需要开启 storage.journal）
+    Compact,
+    /// 专注模式：对任务进行番茄钟计时，不指定任务时选择紧急度最高的任务
+    Focus {
+        id: Option<usize>,
+        /// 番茄个数，默认使用配置中的 pomodoro.rounds
+        #[arg(short, long)]
+        rounds: Option<usize>,
+    },
+}
+
+// 解析可选的排序参数
+fn parse_sort(sort: Option<String>) -> Result<SortSpec, TodoError> {
+    match sort {
+        Some(sort) => SortSpec::parse(&sort),
+        None => Ok(SortSpec::default()),
+    }
+}
+
+// 把命令行上的可选字段转换成 TaskEdit，"-" 表示清除
+fn build_edit(
+    due: Option<String>,
+    tags: Option<String>,
+    depends: Option<String>,
+    project: Option<String>,
+    parent: Option<String>,
+    estimate: Option<String>,
+) -> Result<TaskEdit, TodoError> {
+    let mut edit = TaskEdit::default();
+    match due.as_deref() {
+        None => {}
+        Some("-") => edit.due = Some(None),
+        Some(due) => edit.due = Some(Some(date::parse_due(due)?)),
+    }
+    match tags.as_deref() {
+        None => {}
+        Some("-") => edit.tags = Some(Vec::new()),
+        Some(tags) => edit.tags = Some(task::parse_tags(tags)),
+    }
+    match depends.as_deref() {
+        None => {}
+        Some("-") => edit.depends = Some(Vec::new()),
+        Some(depends) => edit.depends = Some(task::parse_ids(depends)?),
+    }
+    match project.as_deref().map(str::trim) {
+        None => {}
+        Some("-" | "") => edit.project = Some(None),
+        Some(project) => edit.project = Some(Some(project.to_string())),
+    }
+    match parent.as_deref() {
+        None => {}
+        Some("-") => edit.parent = Some(None),
+        Some(parent) => {
+            let parent = parent.trim().parse().map_err(|_| TodoError::InvalidIndex)?;
+            edit.parent = Some(Some(parent));
+        }
+    }
+    match estimate.as_deref() {
+        None => {}
+        Some("-") => edit.estimate = Some(None),
+        Some(estimate) => edit.estimate = Some(Some(Estimate::parse(estimate)?)),
+    }
+    Ok(edit)
+}
+
+// 执行子命令，返回任务列表是否被修改
+pub fn run(command: Command, todo: &mut TodoList, store: &mut Store) -> Result<bool, TodoError> {
+    match command {
+        Command::Add {
+            description,
+            priority,
+            due,
+            tags,
+            depends,
+            project,
+            parent,
+            estimate,
+            merge,
+            force,
+        } => {
+            let edit = build_edit(due, tags, depends, project, parent, estimate)?;
+            let priority =
+                priority.unwrap_or_else(|| todo.config.default_priority.to_english().to_string());
+            let on_duplicate = match (merge, force) {
+                (true, _) => OnDuplicate::Merge,
+                (_, true) => OnDuplicate::Add,
+                _ => OnDuplicate::Ask,
+            };
+            dedupe::add_task(todo, description, &priority, edit, on_duplicate)
+        }
+        Command::List { sort } => {
+            todo.list_tasks(&parse_sort(sort)?)?;
+            Ok(false)
+        }
+        Command::Done { id } => {
+            todo.complete_task(id)?;
+            Ok(true)
+        }
+        Command::Delete { id } => {
+            todo.delete_task(id)?;
+            Ok(true)
+        }
+        Command::Clear => {
+            todo.delete_complete_task()?;
+            Ok(true)
+        }
+        Command::Status { id, status } => {
+            todo.set_status(id, Status::from_str(&status)?)?;
+            Ok(true)
+        }
+        Command::Edit {
+            id,
+            description,
+            priority,
+            due,
+            tags,
+            depends,
+            project,
+            parent,
+            estimate,
+        } => {
+            let mut edit = build_edit(due, tags, depends, project, parent, estimate)?;
+            edit.description = description;
+            edit.priority = priority.as_deref().map(Priority::from_str).transpose()?;
+            todo.edit_task(id, edit)?;
+            Ok(true)
+        }
+        Command::Show { id } => {
+            todo.show_task(id)?;
+            Ok(false)
+        }
+        Command::Note { id, set, append } => {
+            let task = todo.get(id).ok_or(TodoError::InvalidIndex)?;
+            let notes = match (set, append) {
+                (Some(notes), _) => notes,
+                (None, Some(line)) if task.notes.is_empty() => line,
+                (None, Some(line)) => format!("{}\n{}", task.notes, line),
+                (None, None) => return editor::edit_notes(todo, id).map(|_| true),
+            };
+            let edit = TaskEdit {
+                notes: Some(notes),
+                ..Default::default()
+            };
+            todo.edit_task(id, edit)?;
+            Ok(true)
+        }
+        Command::Search { keyword, sort } => {
+            todo.search_tasks(&keyword, &parse_sort(sort)?)?;
+            Ok(false)
+        }
+        Command::Filter { sort, query } => {
+            let filter = Filter::parse(&query.join(" "))?;
+            let title = todo.config.tr("筛选结果", "Filter results");
+            todo.list_filtered(title, &filter, &parse_sort(sort)?)?;
+            Ok(false)
+        }
+        Command::Views => {
+            todo.list_views()?;
+            Ok(false)
+        }
+        Command::View { name, sort } => {
+            todo.show_view(&name, &parse_sort(sort)?)?;
+            Ok(false)
+        }
+        Command::SaveView { name, sort, query } => {
+            let mut views = todo.load_views()?;
+            views.set(&name, &query.join(" "), &sort.unwrap_or_default())?;
+            Ok(false)
+        }
+        Command::DeleteView { name } => {
+            todo.load_views()?.remove(&name)?;
+            Ok(false)
+        }
+        Command::Effort => {
+            todo.show_effort()?;
+            Ok(false)
+        }
+        Command::Plan { budget } => {
+            let budget = budget.as_deref().map(estimate::parse_budget).transpose()?;
+            todo.plan_day(budget)?;
+            Ok(false)
+        }
+        Command::Remind { once } => {
+            remind::run(todo, store, once)?;
+            Ok(false)
+        }
+        Command::Templates => {
+            todo.list_templates()?;
+            Ok(false)
+        }
+        Command::Instantiate {
+            name,
+            vars,
+            date,
+            dry_run,
+        } => {
+            let templates = todo.load_templates()?;
+            let template = templates.get(&name)?;
+            let base = match date {
+                Some(day) => date::parse_due(&day)?.date(),
+                None => date::today(),
+            };
+            todo.instantiate(template, &template::parse_vars(&vars)?, base, dry_run)?;
+            Ok(!dry_run)
+        }
+        Command::ImportMd {
+            markdown,
+            dry_run,
+            headings,
+        } => {
+            let headings = markdown::Headings::from_str(&headings)?;
+            markdown::import(todo, &markdown, headings, dry_run)?;
+            Ok(!dry_run)
+        }
+        Command::ImportTw { json } => {
+            taskwarrior::import(todo, &json)?;
+            Ok(true)
+        }
+        Command::ExportTw { output } => taskwarrior::export(todo, output.as_deref()),
+        Command::Sync {
+            remote,
+            ours,
+            theirs,
+            dry_run,
+        } => {
+            let resolve = match (ours, theirs) {
+                (true, _) => Resolve::Local,
+                (_, true) => Resolve::Remote,
+                _ => Resolve::Ask,
+            };
+            sync::sync(todo, store, &remote, resolve, dry_run)?;
+            Ok(false)
+        }
+        Command::Report {
+            week,
+            from,
+            to,
+            format,
+            output,
+        } => {
+            let (from, to) = match (from, to) {
+                (None, None) => report::week(date::today(), week.unwrap_or(0))?,
+                (from, to) => {
+                    let to = to.map(|to| date::parse_due(&to)).transpose()?.map_or(date::today(), |to| to.date());
+                    let from = match from {
+                        Some(from) => date::parse_due(&from)?.date(),
+                        None => report::week(to, 0)?.0,
+                    };
+                    (from, to)
+                }
+            };
+            let format = report::Format::detect(format.as_deref(), output.as_deref())?;
+            todo.write_report(from, to, format, output.as_deref())?;
+            Ok(false)
+        }
+        Command::Dedupe { yes, dry_run } => Ok(dedupe::dedupe(todo, yes, dry_run)? > 0),
+        Command::Batch { script, keep_going } => {
+            batch::run(todo, store, &script, keep_going)?;
+            Ok(false)
+        }
+        Command::Stats => {
+            todo.show_stats();
+            Ok(false)
+        }
+        Command::Kanban { sort } => {
+            todo.list_kanban(&parse_sort(sort)?)?;
+            Ok(false)
+        }
+        Command::Urgency => {
+            todo.list_by_urgency()?;
+            Ok(false)
+        }
+        Command::Matrix => {
+            todo.show_matrix()?;
+            Ok(false)
+        }
+        Command::Calendar { month, day } => {
+            match day {
+                Some(day) => todo.list_day(calendar::parse_day(&day)?)?,
+                None => {
+                    let month = match month {
+                        Some(month) => calendar::parse_month(&month)?,
+                        None => date::today(),
+                    };
+                    todo.show_calendar(month);
+                }
+            }
+            Ok(false)
+        }
+        Command::Config => {
+            println!("{}", todo.config.to_toml()?);
+            Ok(false)
+        }
+        Command::Log { limit } => {
+            println!("{}", store.log(limit)?);
+            Ok(false)
+        }
+        Command::Restore { rev } => {
+            let restored = store.restore(&rev)?;
+            todo.tasks = restored.tasks;
+            todo.next_id = restored.next_id;
+            println!("⏪ 已恢复到版本 {}", rev);
+            Ok(false)
+        }
+        Command::Passwd => {
+            store.change_passphrase(todo)?;
+            println!("🔐 口令已更新");
+            Ok(false)
+        }
+        Command::Compact => {
+            store.compact(todo)?;
+            println!("🗜️ 已写入快照");
+            Ok(false)
+        }
+        Command::Focus { id, rounds } => {
+            pomodoro::focus(todo, store, id, rounds)?;
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_rejects_an_invalid_date_format() {
+        let mut config = Config::default();
+        let cli = Cli::try_parse_from(["todo_list", "--date-format", "%Q", "list"]).unwrap();
+        assert!(matches!(cli.apply(&mut config), Err(TodoError::Config(_))));
+        assert_eq!(config.date_format, "%Y-%m-%d");
+
+        let cli = Cli::try_parse_from(["todo_list", "--date-format", "%d/%m/%Y", "list"]).unwrap();
+        cli.apply(&mut config).unwrap();
+        assert_eq!(config.date_format, "%d/%m/%Y");
+    }
+}
@@ -0,0 +1,280 @@
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::format::{Item, StrftimeItems};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::dedupe::Dedupe;
+use crate::error::TodoError;
+use crate::estimate::Plan;
+use crate::hooks::Hooks;
+use crate::pomodoro::Pomodoro;
+use crate::remind::Remind;
+use crate::task::Priority;
+use crate::urgency::UrgencyWeights;
+
+// 视图语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    Zh,
+    En,
+}
+
+impl FromStr for Lang {
+    type Err = TodoError;
+
+    fn from_str(s: &str) -> Result<Lang, TodoError> {
+        match s.to_lowercase().as_str() {
+            "zh" | "zh-cn" | "中文" => Ok(Lang::Zh),
+            "en" | "english" => Ok(Lang::En),
+            _ => Err(TodoError::Config(format!("不支持的语言：{}", s))),
+        }
+    }
+}
+
+// 何时使用颜色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorMode {
+    type Err = TodoError;
+
+    fn from_str(s: &str) -> Result<ColorMode, TodoError> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" | "on" => Ok(ColorMode::Always),
+            "never" | "off" => Ok(ColorMode::Never),
+            _ => Err(TodoError::Config(format!(
+                "无效的颜色模式：{}，请使用 auto/always/never",
+                s
+            ))),
+        }
+    }
+}
+
+// 颜色设置，颜色名可用 red/green/yellow/blue/magenta/cyan/white/gray 或 none
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Colors {
+    pub mode: ColorMode,
+    pub high: String,
+    pub medium: String,
+    pub low: String,
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Colors {
+            mode: ColorMode::Auto,
+            high: "red".to_string(),
+            medium: "yellow".to_string(),
+            low: "green".to_string(),
+        }
+    }
+}
+
+// 数据存储方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Storage {
+    // 把数据文件放进 git 仓库，每次修改自动提交
+    pub git: bool,
+    // 用口令加密数据文件
+    pub encrypt: bool,
+    // 每次修改只追加到日志文件，数据文件作为快照
+    pub journal: bool,
+    // 日志达到多少条时写一次快照并清空日志
+    pub snapshot_every: usize,
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Storage {
+            git: false,
+            encrypt: false,
+            journal: false,
+            snapshot_every: 100,
+        }
+    }
+}
+
+// 配置文件内容，缺省的项使用默认值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // 配置文件自身的位置
+    #[serde(skip)]
+    pub path: PathBuf,
+    // 标准输入是否是终端；管道、cron 和批处理中不能询问用户
+    #[serde(skip)]
+    pub interactive: bool,
+    // 数据文件位置，默认放在 XDG 数据目录
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_file: Option<PathBuf>,
+    // 新任务的默认优先级
+    pub default_priority: Priority,
+    // 是否显示 emoji
+    pub emoji: bool,
+    // 日期格式，使用 chrono 的格式字符串
+    pub date_format: String,
+    // 视图语言：只影响列表、详情、看板、统计、日历和报告等视图的标题和字段名，
+    // 操作提示和错误信息固定为中文
+    #[serde(alias = "language")]
+    pub view_language: Lang,
+    pub colors: Colors,
+    pub storage: Storage,
+    pub hooks: Hooks,
+    pub pomodoro: Pomodoro,
+    pub plan: Plan,
+    pub remind: Remind,
+    pub dedupe: Dedupe,
+    pub urgency: UrgencyWeights,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            path: default_config_path(),
+            interactive: io::stdin().is_terminal(),
+            data_file: None,
+            default_priority: Priority::Medium,
+            emoji: true,
+            date_format: "%Y-%m-%d".to_string(),
+            view_language: Lang::Zh,
+            colors: Colors::default(),
+            storage: Storage::default(),
+            hooks: Hooks::default(),
+            pomodoro: Pomodoro::default(),
+            plan: Plan::default(),
+            remind: Remind::default(),
+            dedupe: Dedupe::default(),
+            urgency: UrgencyWeights::default(),
+        }
+    }
+}
+
+// 检查日期格式，无效的格式在显示日期时会导致崩溃
+pub fn check_date_format(format: &str) -> Result<(), TodoError> {
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(TodoError::Config(format!("无效的日期格式 {}，可用 %Y-%m-%d 这样的格式", format)));
+    }
+    Ok(())
+}
+
+// 读取 TOML 文件，文件不存在时返回 None
+pub fn read_toml<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, TodoError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)?;
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|e| TodoError::Config(format!("{}: {}", path.display(), e)))
+}
+
+// 写入 TOML 文件，目录不存在时先创建
+pub fn write_toml<T: Serialize>(path: &Path, value: &T) -> Result<(), TodoError> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let content = toml::to_string_pretty(value).map_err(|e| TodoError::Config(e.to_string()))?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+// 展开路径开头的 ~
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+// XDG 目录：优先使用环境变量，否则使用 ~ 下的默认位置
+fn xdg_dir(var: &str, fallback: &str) -> PathBuf {
+    env::var_os(var)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| expand_home(Path::new(fallback)))
+        .join("todo_list")
+}
+
+// 默认配置文件：$XDG_CONFIG_HOME/todo_list/config.toml
+pub fn default_config_path() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", "~/.config").join("config.toml")
+}
+
+// 默认数据文件：$XDG_DATA_HOME/todo_list/todo.json
+pub fn default_data_file() -> PathBuf {
+    xdg_dir("XDG_DATA_HOME", "~/.local/share").join("todo.json")
+}
+
+impl Config {
+    // 读取配置文件，文件不存在时使用默认配置
+    pub fn load(path: &Path) -> Result<Config, TodoError> {
+        let mut config: Config = read_toml(path)?.unwrap_or_default();
+        check_date_format(&config.date_format)?;
+        config.urgency.normalize();
+        config.path = path.to_path_buf();
+        config.interactive = io::stdin().is_terminal();
+        Ok(config)
+    }
+
+    // 和配置文件放在同一目录下的其他文件，例如 views.toml、templates.toml
+    pub fn sibling_path(&self, name: &str) -> PathBuf {
+        self.path
+            .parent()
+            .map(|dir| dir.join(name))
+            .unwrap_or_else(|| PathBuf::from(name))
+    }
+
+    // 实际使用的数据文件
+    pub fn data_file(&self) -> PathBuf {
+        match &self.data_file {
+            Some(path) => expand_home(path),
+            None => default_data_file(),
+        }
+    }
+
+    // 以 TOML 格式输出生效的配置
+    pub fn to_toml(&self) -> Result<String, TodoError> {
+        let mut effective = self.clone();
+        effective.data_file = Some(self.data_file());
+        let content =
+            toml::to_string_pretty(&effective).map_err(|e| TodoError::Config(e.to_string()))?;
+        let state = if self.path.exists() { "" } else { "（不存在，使用默认值）" };
+        Ok(format!("# 配置文件: {}{}\n{}", self.path.display(), state, content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_date_formats_are_rejected() {
+        for format in ["%Y-%m-%d", "%Y/%m/%d %a", "%d.%m.", "日期 %F"] {
+            assert!(check_date_format(format).is_ok(), "{}", format);
+        }
+        for format in ["%Q", "%Y-%", "%-"] {
+            assert!(matches!(check_date_format(format), Err(TodoError::Config(_))), "{}", format);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "date_format = \"%Y-%m-%d\"\n").unwrap();
+        assert!(Config::load(&path).is_ok());
+        fs::write(&path, "date_format = \"%Q\"\n").unwrap();
+        assert!(matches!(Config::load(&path), Err(TodoError::Config(_))));
+    }
+}
@@ -0,0 +1,184 @@
+use std::env;
+
+use argon2::Argon2;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::TodoError;
+
+// 非交互使用时可以通过环境变量提供口令
+const PASSPHRASE_ENV: &str = "TODO_LIST_PASSPHRASE";
+const NEW_PASSPHRASE_ENV: &str = "TODO_LIST_NEW_PASSPHRASE";
+
+const CIPHER: &str = "chacha20poly1305";
+const KDF: &str = "argon2id";
+const SALT_LEN: usize = 16;
+
+// 加密后的数据文件内容
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    encrypted: String,
+    kdf: String,
+    salt: String,
+    nonce: String,
+    data: String,
+}
+
+// 由口令派生出的密钥，一次会话中只需输入一次口令
+#[derive(Clone)]
+pub struct Sealer {
+    salt: Vec<u8>,
+    key: Key,
+}
+
+fn decode(field: &str, s: &str) -> Result<Vec<u8>, TodoError> {
+    BASE64
+        .decode(s)
+        .map_err(|e| TodoError::Crypto(format!("{} 字段损坏：{}", field, e)))
+}
+
+impl Sealer {
+    fn derive(passphrase: &str, salt: Vec<u8>) -> Result<Self, TodoError> {
+        let mut key = Key::default();
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| TodoError::Crypto(e.to_string()))?;
+        Ok(Sealer { salt, key })
+    }
+
+    // 使用新口令，每次生成新的盐
+    pub fn new(passphrase: &str) -> Result<Self, TodoError> {
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Sealer::derive(passphrase, salt)
+    }
+
+    // 加密成一行 JSON，每次使用新的随机 nonce
+    pub fn seal(&self, plaintext: &str) -> Result<String, TodoError> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let data = ChaCha20Poly1305::new(&self.key)
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|e| TodoError::Crypto(e.to_string()))?;
+        let envelope = Envelope {
+            encrypted: CIPHER.to_string(),
+            kdf: KDF.to_string(),
+            salt: BASE64.encode(&self.salt),
+            nonce: BASE64.encode(nonce),
+            data: BASE64.encode(data),
+        };
+        Ok(serde_json::to_string(&envelope)?)
+    }
+
+    // 解密；认证失败说明口令错误或文件被改动过
+    fn open(&self, envelope: &Envelope) -> Result<String, TodoError> {
+        let nonce = decode("nonce", &envelope.nonce)?;
+        if nonce.len() != 12 {
+            return Err(TodoError::Crypto("nonce 长度不正确".to_string()));
+        }
+        let data = decode("data", &envelope.data)?;
+        let plaintext = ChaCha20Poly1305::new(&self.key)
+            .decrypt(Nonce::from_slice(&nonce), data.as_slice())
+            .map_err(|_| TodoError::WrongPassphrase)?;
+        String::from_utf8(plaintext).map_err(|e| TodoError::Crypto(e.to_string()))
+    }
+}
+
+// 判断数据文件是否是加密格式
+pub fn is_sealed(value: &Value) -> bool {
+    value.get("encrypted").is_some_and(Value::is_string)
+}
+
+// 解密数据文件，盐相同时复用已有的密钥，否则询问口令
+pub fn open(value: Value, sealer: &mut Option<Sealer>) -> Result<String, TodoError> {
+    let envelope: Envelope = serde_json::from_value(value)?;
+    if envelope.encrypted != CIPHER || envelope.kdf != KDF {
+        return Err(TodoError::Crypto(format!(
+            "不支持的加密方式：{}/{}",
+            envelope.encrypted, envelope.kdf
+        )));
+    }
+    let salt = decode("salt", &envelope.salt)?;
+    if let Some(current) = sealer
+        && current.salt == salt
+    {
+        return current.open(&envelope);
+    }
+    let passphrase = read_passphrase()?;
+    let opened = Sealer::derive(&passphrase, salt)?;
+    let plaintext = opened.open(&envelope)?;
+    *sealer = Some(opened);
+    Ok(plaintext)
+}
+
+fn prompt(message: &str) -> Result<String, TodoError> {
+    rpassword::prompt_password(message).map_err(|e| TodoError::Crypto(format!("无法读取口令：{}", e)))
+}
+
+// 读取当前口令
+pub fn read_passphrase() -> Result<String, TodoError> {
+    match env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) => Ok(passphrase),
+        Err(_) => prompt("🔑 请输入口令: "),
+    }
+}
+
+// 读取新口令，交互输入时需要确认一遍
+pub fn read_new_passphrase() -> Result<String, TodoError> {
+    if let Ok(passphrase) = env::var(NEW_PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+    let passphrase = prompt("🔑 请设置新口令: ")?;
+    if passphrase.is_empty() {
+        return Err(TodoError::Crypto("口令不能为空".to_string()));
+    }
+    if prompt("🔑 请再输入一次: ")? != passphrase {
+        return Err(TodoError::Crypto("两次输入的口令不一致".to_string()));
+    }
+    Ok(passphrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let sealer = Sealer::new("口令").unwrap();
+        let sealed = sealer.seal("{\"tasks\":[]}").unwrap();
+        let value: Value = serde_json::from_str(&sealed).unwrap();
+        assert!(is_sealed(&value));
+        assert!(!sealed.contains("tasks"));
+
+        let mut current = Some(sealer);
+        assert_eq!(open(value, &mut current).unwrap(), "{\"tasks\":[]}");
+    }
+
+    #[test]
+    fn nonce_is_fresh_each_time() {
+        let sealer = Sealer::new("口令").unwrap();
+        assert_ne!(sealer.seal("same").unwrap(), sealer.seal("same").unwrap());
+    }
+
+    #[test]
+    fn wrong_key_is_rejected() {
+        let sealer = Sealer::new("对的口令").unwrap();
+        let envelope: Envelope = serde_json::from_str(&sealer.seal("secret").unwrap()).unwrap();
+        let wrong = Sealer::derive("错的口令", sealer.salt.clone()).unwrap();
+        assert!(matches!(wrong.open(&envelope), Err(TodoError::WrongPassphrase)));
+    }
+
+    #[test]
+    fn tampered_data_is_rejected() {
+        let sealer = Sealer::new("口令").unwrap();
+        let mut envelope: Envelope = serde_json::from_str(&sealer.seal("secret").unwrap()).unwrap();
+        let mut data = decode("data", &envelope.data).unwrap();
+        data[0] ^= 1;
+        envelope.data = BASE64.encode(data);
+        assert!(matches!(sealer.open(&envelope), Err(TodoError::WrongPassphrase)));
+    }
+}
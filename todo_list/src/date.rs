@@ -0,0 +1,91 @@
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::error::TodoError;
+
+// 当前本地时间
+pub fn now() -> NaiveDateTime {
+    Local::now().naive_local()
+}
+
+// 今天的日期
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+// 只给了日期时，截止时间算到当天结束
+fn end_of_day(date: NaiveDate) -> NaiveDateTime {
+    date.and_time(NaiveTime::from_hms_opt(23, 59, 0).unwrap())
+}
+
+// 解析截止时间：today / tomorrow / +3d / +2w / 2024-05-01 / 2024-05-01 18:00
+pub fn parse_due(s: &str) -> Result<NaiveDateTime, TodoError> {
+    parse_due_from(s, today())
+}
+
+// 以 base 为“今天”解析截止时间，模板中的相对日期用它计算
+pub fn parse_due_from(s: &str, base: NaiveDate) -> Result<NaiveDateTime, TodoError> {
+    let s = s.trim();
+    let invalid = || TodoError::InvalidDate(s.to_string());
+    // 偏移太大、超出日期范围时报错
+    let after = |days: i64| {
+        Duration::try_days(days)
+            .and_then(|offset| base.checked_add_signed(offset))
+            .map(end_of_day)
+            .ok_or_else(invalid)
+    };
+    match s.to_lowercase().as_str() {
+        "today" | "今天" => return Ok(end_of_day(base)),
+        "tomorrow" | "明天" => return after(1),
+        _ => {}
+    }
+
+    if let Some(offset) = s.strip_prefix('+') {
+        let days = match offset.strip_suffix('w') {
+            Some(weeks) => weeks.parse::<i64>().ok().map(|w| w.checked_mul(7).ok_or_else(invalid)),
+            None => offset.strip_suffix('d').and_then(|d| d.parse::<i64>().ok()).map(Ok),
+        };
+        if let Some(days) = days {
+            return after(days?);
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(end_of_day(date));
+    }
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parse_due_reads_relative_and_absolute_dates() {
+        let base = day(2024, 2, 27);
+        assert_eq!(parse_due_from("today", base).unwrap(), end_of_day(base));
+        assert_eq!(parse_due_from("明天", base).unwrap(), end_of_day(day(2024, 2, 28)));
+        assert_eq!(parse_due_from("+3d", base).unwrap(), end_of_day(day(2024, 3, 1)));
+        assert_eq!(parse_due_from("+2w", base).unwrap(), end_of_day(day(2024, 3, 12)));
+        assert_eq!(parse_due_from("+-1d", base).unwrap(), end_of_day(day(2024, 2, 26)));
+        assert_eq!(parse_due_from(" 2024-05-01 ", base).unwrap(), end_of_day(day(2024, 5, 1)));
+        assert_eq!(
+            parse_due_from("2024-05-01 18:30", base).unwrap(),
+            day(2024, 5, 1).and_hms_opt(18, 30, 0).unwrap()
+        );
+        for s in ["", "+d", "+3x", "2024-13-01", "next week"] {
+            assert!(parse_due_from(s, base).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn parse_due_rejects_offsets_out_of_range() {
+        let base = day(2024, 5, 1);
+        for s in ["+9999999999d", "+99999999999999999w", "+9223372036854775807d", "+-9999999999d"] {
+            assert!(matches!(parse_due_from(s, base), Err(TodoError::InvalidDate(_))), "{}", s);
+        }
+        assert!(parse_due_from("tomorrow", NaiveDate::MAX).is_err());
+    }
+}
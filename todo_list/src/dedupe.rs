@@ -0,0 +1,489 @@
+use std::cmp::Ordering;
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::TodoError;
+use crate::hooks::HookEvent;
+use crate::sort::SortSpec;
+use crate::task::{Task, TaskEdit};
+use crate::todo::TodoList;
+
+// 重复任务检测设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Dedupe {
+    // 相似度达到多少算重复，0 到 1，1 表示规范化后完全相同
+    pub threshold: f64,
+    // 添加任务时是否检查重复
+    pub check_on_add: bool,
+}
+
+impl Default for Dedupe {
+    fn default() -> Self {
+        Dedupe {
+            threshold: 0.8,
+            check_on_add: true,
+        }
+    }
+}
+
+// 规范化描述：转成小写，只保留文字和数字，忽略空白和标点
+pub fn normalize(s: &str) -> String {
+    s.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+// 相邻两个字符组成的片段，排好序便于比较
+fn bigrams(s: &str) -> Vec<(char, char)> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut pairs: Vec<(char, char)> = chars.windows(2).map(|w| (w[0], w[1])).collect();
+    pairs.sort_unstable();
+    pairs
+}
+
+// 两个规范化后的描述的相似度，0 到 1（Dice 系数），按字符计算，中文和英文都适用
+fn dice(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let (a, b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let (mut i, mut j, mut common) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                common += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    2.0 * common as f64 / (a.len() + b.len()) as f64
+}
+
+// 规范化后少于这么多字的描述只在完全相同时算重复
+const MIN_FUZZY_LEN: usize = 4;
+
+// 用来比较的描述：规范化后的文字，以及其中的数字
+struct Key {
+    text: String,
+    numbers: Vec<String>,
+}
+
+impl Key {
+    fn new(description: &str) -> Key {
+        let numbers = description
+            .split(|c: char| !c.is_numeric())
+            .filter(|n| !n.is_empty())
+            .map(str::to_string)
+            .collect();
+        Key {
+            text: normalize(description),
+            numbers,
+        }
+    }
+
+    // 数字不同的一定不是重复，例如 bug #123 和 bug #124、v1.2 和 v1.3；
+    // 太短的描述相差一个字就可能是不同的事，只比较是否相同
+    fn similarity(&self, other: &Key) -> f64 {
+        if self.numbers != other.numbers {
+            return 0.0;
+        }
+        if self.text.chars().count().min(other.text.chars().count()) < MIN_FUZZY_LEN {
+            return if self.text == other.text { 1.0 } else { 0.0 };
+        }
+        dice(&self.text, &other.text)
+    }
+}
+
+// 添加任务时发现重复后的选择
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OnDuplicate {
+    Ask,
+    Merge,
+    Add,
+}
+
+// 询问是否合并，返回 Some(true) 合并、Some(false) 跳过，None 表示取消；输入结束也算取消
+fn ask(prompt: &str) -> Option<bool> {
+    loop {
+        print!("{}", prompt);
+        let _ = io::stdout().flush();
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+            return None;
+        }
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes" | "是" => return Some(true),
+            "n" | "no" | "否" => return Some(false),
+            "q" | "quit" | "取消" => return None,
+            _ => println!("🙅请输入 y、n 或 q"),
+        }
+    }
+}
+
+impl TodoList {
+    // 和描述相似的未结束任务，按相似度从高到低排列
+    pub fn similar_tasks(&self, description: &str) -> Vec<(&Task, f64)> {
+        let key = Key::new(description);
+        let threshold = self.config.dedupe.threshold;
+        let mut similar: Vec<(&Task, f64)> = self
+            .tasks
+            .iter()
+            .filter(|task| !task.status.is_closed())
+            .map(|task| (task, key.similarity(&Key::new(&task.description))))
+            .filter(|(_, score)| *score >= threshold)
+            .collect();
+        similar.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.id.cmp(&b.0.id)));
+        similar
+    }
+
+    // 未结束任务中的重复分组，每组第一个是最早的任务，其余的和它相似
+    pub fn duplicate_groups(&self) -> Vec<Vec<usize>> {
+        let threshold = self.config.dedupe.threshold;
+        let open: Vec<(usize, Key)> = self
+            .tasks
+            .iter()
+            .filter(|task| !task.status.is_closed())
+            .map(|task| (task.id, Key::new(&task.description)))
+            .collect();
+        let mut grouped = vec![false; open.len()];
+        let mut groups = Vec::new();
+        for i in 0..open.len() {
+            if grouped[i] {
+                continue;
+            }
+            let mut group = vec![open[i].0];
+            for j in i + 1..open.len() {
+                if !grouped[j] && open[i].1.similarity(&open[j].1) >= threshold {
+                    grouped[j] = true;
+                    group.push(open[j].0);
+                }
+            }
+            if group.len() > 1 {
+                groups.push(group);
+            }
+        }
+        groups
+    }
+
+    // id 是否是 task 自己或它的祖先
+    fn is_ancestor(&self, id: usize, task: usize) -> bool {
+        let mut current = Some(task);
+        while let Some(ancestor) = current {
+            if ancestor == id {
+                return true;
+            }
+            current = self.get(ancestor).and_then(|t| t.parent);
+        }
+        false
+    }
+
+    // 把 other 的内容并入 keep：标签、依赖和备注取并集，优先级取高的，截止时间取早的，
+    // keep 没有的项目、父任务和预估用 other 的
+    fn merge_edit(&self, keep: &Task, other: &Task) -> TaskEdit {
+        let mut edit = TaskEdit::default();
+        if other.priority < keep.priority {
+            edit.priority = Some(other.priority);
+        }
+        if let Some(due) = other.due
+            && keep.due.is_none_or(|keep_due| due < keep_due)
+        {
+            edit.due = Some(Some(due));
+        }
+        let mut tags = keep.tags.clone();
+        for tag in &other.tags {
+            if !tags.iter().any(|t| t.to_lowercase() == tag.to_lowercase()) {
+                tags.push(tag.clone());
+            }
+        }
+        if tags.len() > keep.tags.len() {
+            edit.tags = Some(tags);
+        }
+        let mut depends = keep.depends.clone();
+        depends.retain(|&dep| dep != other.id);
+        for &dep in &other.depends {
+            if dep != keep.id && !depends.contains(&dep) {
+                depends.push(dep);
+            }
+        }
+        if depends != keep.depends {
+            edit.depends = Some(depends);
+        }
+        if keep.project.is_none() && other.project.is_some() {
+            edit.project = Some(other.project.clone());
+        }
+        if keep.parent == Some(other.id) {
+            edit.parent = Some(other.parent.filter(|&parent| !self.is_ancestor(keep.id, parent)));
+        } else if keep.parent.is_none()
+            && let Some(parent) = other.parent
+            && !self.is_ancestor(keep.id, parent)
+        {
+            edit.parent = Some(Some(parent));
+        }
+        if keep.estimate.is_none() && other.estimate.is_some() {
+            edit.estimate = Some(other.estimate);
+        }
+        let notes = other.notes.trim();
+        if !notes.is_empty() && !keep.notes.contains(notes) {
+            edit.notes = Some(match keep.notes.is_empty() {
+                true => notes.to_string(),
+                false => format!("{}\n{}", keep.notes.trim_end(), notes),
+            });
+        }
+        edit
+    }
+
+    // 把还没加入列表的新任务并入已有的任务
+    pub fn merge_new_task(&mut self, keep: usize, task: &Task) -> Result<(), TodoError> {
+        let keep_task = self.get(keep).ok_or(TodoError::InvalidIndex)?;
+        let edit = self.merge_edit(keep_task, task);
+        self.edit_task(keep, edit)?;
+        println!("🔗 已将新任务并入任务 {}", keep);
+        Ok(())
+    }
+
+    // 把任务 other 合并到 keep 后删除 other，其他任务对 other 的引用改为指向 keep
+    pub fn merge_tasks(&mut self, keep: usize, other: usize) -> Result<(), TodoError> {
+        if keep == other {
+            return Err(TodoError::InvalidIndex);
+        }
+        let keep_task = self.get(keep).ok_or(TodoError::InvalidIndex)?;
+        let other_task = self.get(other).ok_or(TodoError::InvalidIndex)?.clone();
+        let edit = self.merge_edit(keep_task, &other_task);
+        self.run_hooks(HookEvent::Delete, other_task.clone())?;
+        self.edit_task(keep, edit)?;
+
+        self.tasks.update(keep, |task| {
+            task.pomodoros.extend(other_task.pomodoros.iter().copied());
+            task.pomodoros.sort();
+        });
+        let dependents: Vec<usize> = self.tasks.dependents(other).map(|t| t.id).collect();
+        for id in dependents {
+            self.tasks.update(id, |task| {
+                task.depends.retain(|&dep| dep != other);
+                if id != keep && !task.depends.contains(&keep) {
+                    task.depends.push(keep);
+                }
+            });
+        }
+        let children: Vec<usize> = self.tasks.children(other).map(|t| t.id).collect();
+        for id in children {
+            let parent = (!self.is_ancestor(id, keep)).then_some(keep);
+            self.tasks.update(id, |task| task.parent = parent);
+        }
+        self.tasks.remove(other);
+        println!("🔗 已将任务 {} '{}' 合并到任务 {}", other, other_task.description, keep);
+        Ok(())
+    }
+}
+
+// 添加任务，先检查重复：发现相似的未结束任务时提示，可以并入最相似的那个。
+// 返回任务列表是否被修改，取消添加时为 false
+pub fn add_task(
+    todo: &mut TodoList,
+    description: String,
+    priority_str: &str,
+    edit: TaskEdit,
+    on_duplicate: OnDuplicate,
+) -> Result<bool, TodoError> {
+    let task = todo.new_task(description, priority_str, edit)?;
+    let similar: Vec<(usize, String, f64)> = match on_duplicate {
+        OnDuplicate::Add => Vec::new(),
+        _ if !todo.config.dedupe.check_on_add => Vec::new(),
+        _ => todo
+            .similar_tasks(&task.description)
+            .into_iter()
+            .map(|(task, score)| (task.id, task.description.clone(), score))
+            .collect(),
+    };
+    if let Some(&(keep, _, _)) = similar.first() {
+        println!("⚠️ 发现 {} 个相似的未完成任务：", similar.len());
+        for (id, description, score) in &similar {
+            println!("  {} {}（相似度 {:.0}%）", id, description, score * 100.0);
+        }
+        let merge = match on_duplicate {
+            OnDuplicate::Merge => Some(true),
+            _ if !todo.config.interactive => {
+                println!("⚠️ 不是交互式终端，仍然添加；可用 --merge 并入或 --force 跳过检查");
+                Some(false)
+            }
+            _ => ask(&format!("并入任务 {}？[y] 合并 / [n] 仍然添加 / [q] 取消：", keep)),
+        };
+        match merge {
+            Some(true) => {
+                todo.merge_new_task(keep, &task)?;
+                return Ok(true);
+            }
+            Some(false) => {}
+            None => {
+                println!("已取消添加");
+                return Ok(false);
+            }
+        }
+    }
+    todo.insert_task(task)?;
+    println!("任务已添加！👌");
+    Ok(true)
+}
+
+// 查找并合并已有的重复任务，每组保留最早的任务；
+// yes 时不询问全部合并，dry_run 时只列出。返回合并的任务数
+pub fn dedupe(todo: &mut TodoList, yes: bool, dry_run: bool) -> Result<usize, TodoError> {
+    if !yes && !dry_run && !todo.config.interactive {
+        return Err(TodoError::NotInteractive("请加上 --yes 全部合并，或 --dry-run 只列出".to_string()));
+    }
+    let groups = todo.duplicate_groups();
+    if groups.is_empty() {
+        println!("✨ 没有发现重复的任务");
+        return Ok(0);
+    }
+    let sort = SortSpec::parse("id")?;
+    let mut merged = 0;
+    'groups: for (i, group) in groups.iter().enumerate() {
+        let tasks: Vec<&Task> = group.iter().filter_map(|&id| todo.get(id)).collect();
+        todo.print_tasks(&format!("🔍 第 {}/{} 组重复任务：", i + 1, groups.len()), tasks, &sort);
+        if dry_run {
+            continue;
+        }
+        let (keep, others) = group.split_first().expect("重复分组至少有两个任务");
+        for &other in others {
+            if !yes {
+                match ask(&format!("把任务 {} 合并到任务 {}？[y] 合并 / [n] 跳过 / [q] 结束：", other, keep)) {
+                    Some(true) => {}
+                    Some(false) => continue,
+                    None => break 'groups,
+                }
+            }
+            match todo.merge_tasks(*keep, other) {
+                Ok(()) => merged += 1,
+                Err(e) => println!("🙅‍♂️{}", e),
+            }
+        }
+    }
+    match dry_run {
+        true => println!("\n📋 共发现 {} 组重复任务，未做修改", groups.len()),
+        false => println!("\n📋 共发现 {} 组重复任务，合并了 {} 个任务", groups.len(), merged),
+    }
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Priority, Status};
+
+    fn todo_with(descriptions: &[&str]) -> TodoList {
+        let mut todo = TodoList::new();
+        for description in descriptions {
+            todo.add_task(description.to_string(), "medium").unwrap();
+        }
+        todo
+    }
+
+    fn similarity(a: &str, b: &str) -> f64 {
+        Key::new(a).similarity(&Key::new(b))
+    }
+
+    #[test]
+    fn normalize_ignores_case_spaces_and_punctuation() {
+        assert_eq!(normalize("  Buy Milk! "), "buymilk");
+        assert_eq!(normalize("写 周报。"), "写周报");
+        assert_eq!(normalize("Fix bug #123"), "fixbug123");
+        assert_eq!(normalize("—…"), "");
+    }
+
+    #[test]
+    fn dice_scores_shared_bigrams() {
+        assert_eq!(dice("buymilk", "buymilk"), 1.0);
+        assert_eq!(dice("abcd", "wxyz"), 0.0);
+        assert_eq!(dice("a", "ab"), 0.0);
+        // ab bc cd 和 ab bc ce 有两个相同的片段
+        assert!((dice("abcd", "abce") - 2.0 * 2.0 / 6.0).abs() < 1e-9);
+        assert!((dice("写周报告", "写周报") - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn different_numbers_are_never_duplicates() {
+        assert_eq!(similarity("Fix bug #123", "Fix bug #124"), 0.0);
+        assert_eq!(similarity("Release v1.2", "Release v1.3"), 0.0);
+        assert_eq!(similarity("Release v1.2", "release v1.2!"), 1.0);
+        assert!(similarity("Prepare the weekly report", "Prepare weekly report") >= 0.8);
+    }
+
+    #[test]
+    fn short_descriptions_must_match_exactly() {
+        assert_eq!(similarity("写周报", "写周报。"), 1.0);
+        assert_eq!(similarity("写周报", "写周报告"), 0.0);
+        assert_eq!(similarity("tea", "tee"), 0.0);
+    }
+
+    #[test]
+    fn duplicate_groups_skip_closed_tasks() {
+        let mut todo = todo_with(&[
+            "Prepare the weekly report",
+            "Buy milk",
+            "prepare weekly report",
+            "Fix bug #123",
+            "Fix bug #124",
+            "buy milk.",
+        ]);
+        assert_eq!(todo.duplicate_groups(), vec![vec![1, 3], vec![2, 6]]);
+
+        todo.set_status(6, Status::Done).unwrap();
+        assert_eq!(todo.duplicate_groups(), vec![vec![1, 3]]);
+    }
+
+    #[test]
+    fn merge_tasks_combines_fields_and_retargets_references() {
+        let mut todo = todo_with(&["Buy milk", "buy milk.", "Go shopping", "Put milk in the fridge"]);
+        let mut keep = todo.get(1).unwrap().clone();
+        keep.priority = Priority::Low;
+        keep.tags = vec!["home".to_string()];
+        todo.tasks.insert(keep);
+        let mut other = todo.get(2).unwrap().clone();
+        other.priority = Priority::High;
+        other.tags = vec!["HOME".to_string(), "errand".to_string()];
+        other.notes = "低脂的".to_string();
+        other.parent = Some(3);
+        todo.tasks.insert(other);
+        let mut dependent = todo.get(4).unwrap().clone();
+        dependent.depends = vec![2];
+        dependent.parent = Some(2);
+        todo.tasks.insert(dependent);
+
+        todo.merge_tasks(1, 2).unwrap();
+
+        assert!(todo.get(2).is_none());
+        let kept = todo.get(1).unwrap();
+        assert_eq!(kept.priority, Priority::High);
+        assert_eq!(kept.tags, vec!["home", "errand"]);
+        assert_eq!(kept.notes, "低脂的");
+        assert_eq!(kept.parent, Some(3));
+        let dependent = todo.get(4).unwrap();
+        assert_eq!(dependent.depends, vec![1]);
+        assert_eq!(dependent.parent, Some(1));
+        assert_eq!(todo.tasks.dependents(2).count(), 0);
+        assert_eq!(todo.tasks.children(2).count(), 0);
+    }
+
+    #[test]
+    fn merge_tasks_drops_self_references() {
+        let mut todo = todo_with(&["Buy milk", "buy milk."]);
+        let mut keep = todo.get(1).unwrap().clone();
+        keep.depends = vec![2];
+        keep.parent = Some(2);
+        todo.tasks.insert(keep);
+
+        todo.merge_tasks(1, 2).unwrap();
+
+        let kept = todo.get(1).unwrap();
+        assert!(kept.depends.is_empty());
+        assert_eq!(kept.parent, None);
+        assert!(todo.merge_tasks(1, 1).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use std::io::IsTerminal;
+
+use chrono::{NaiveDateTime, NaiveTime};
+
+use crate::config::{ColorMode, Config, Lang};
+use crate::task::{Priority, Status};
+
+// 颜色名对应的 ANSI 转义码
+fn ansi_code(color: &str) -> Option<&'static str> {
+    match color.to_lowercase().as_str() {
+        "red" => Some("31"),
+        "green" => Some("32"),
+        "yellow" => Some("33"),
+        "blue" => Some("34"),
+        "magenta" => Some("35"),
+        "cyan" => Some("36"),
+        "white" => Some("37"),
+        "gray" | "grey" => Some("90"),
+        _ => None,
+    }
+}
+
+impl Config {
+    // 根据视图语言选择文字，只用于视图，提示和错误信息不翻译
+    pub fn tr<'a>(&self, zh: &'a str, en: &'a str) -> &'a str {
+        match self.view_language {
+            Lang::Zh => zh,
+            Lang::En => en,
+        }
+    }
+
+    pub fn use_color(&self) -> bool {
+        match self.colors.mode {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            // 遵循 NO_COLOR 约定：设置了非空的 NO_COLOR 时不自动输出颜色
+            ColorMode::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                    && std::io::stdout().is_terminal()
+            }
+        }
+    }
+
+    // 给文字加上颜色
+    pub fn paint(&self, text: &str, color: &str) -> String {
+        match ansi_code(color) {
+            Some(code) if self.use_color() => format!("\x1b[{}m{}\x1b[0m", code, text),
+            _ => text.to_string(),
+        }
+    }
+
+    pub fn priority_color(&self, priority: Priority) -> &str {
+        match priority {
+            Priority::High => &self.colors.high,
+            Priority::Medium => &self.colors.medium,
+            Priority::Low => &self.colors.low,
+        }
+    }
+
+    pub fn priority_name(&self, priority: Priority) -> &'static str {
+        match self.view_language {
+            Lang::Zh => priority.to_string(),
+            Lang::En => priority.to_english(),
+        }
+    }
+
+    pub fn status_name(&self, status: Status) -> &'static str {
+        match self.view_language {
+            Lang::Zh => status.to_string(),
+            Lang::En => status.to_english(),
+        }
+    }
+
+    // 优先级文字，例如 "🔴 高"，关闭 emoji 时只有文字
+    pub fn priority_text(&self, priority: Priority) -> String {
+        if self.emoji {
+            format!("{} {}", priority.to_emoji(), self.priority_name(priority))
+        } else {
+            self.priority_name(priority).to_string()
+        }
+    }
+
+    // 带颜色的优先级文字
+    pub fn priority_label(&self, priority: Priority) -> String {
+        self.paint(&self.priority_text(priority), self.priority_color(priority))
+    }
+
+    // 状态标记，开启 emoji 时只显示 emoji，否则显示文字
+    pub fn status_mark(&self, status: Status) -> String {
+        if self.emoji {
+            status.to_emoji().to_string()
+        } else {
+            format!("[{}]", self.status_name(status))
+        }
+    }
+
+    // 带 emoji 的状态名，用于标题
+    pub fn status_label(&self, status: Status) -> String {
+        if self.emoji {
+            format!("{} {}", status.to_emoji(), self.status_name(status))
+        } else {
+            self.status_name(status).to_string()
+        }
+    }
+
+    // 关闭 emoji 时返回空字符串
+    pub fn icon<'a>(&self, emoji: &'a str) -> &'a str {
+        if self.emoji { emoji } else { "" }
+    }
+
+    // 显示截止时间，当天结束的截止时间只显示日期
+    pub fn format_due(&self, due: NaiveDateTime) -> String {
+        if due.time() == NaiveTime::from_hms_opt(23, 59, 0).unwrap() {
+            due.format(&self.date_format).to_string()
+        } else {
+            self.format_datetime(due)
+        }
+    }
+
+    pub fn format_datetime(&self, time: NaiveDateTime) -> String {
+        time.format(&format!("{} %H:%M", self.date_format)).to_string()
+    }
+}
@@ -0,0 +1,130 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::error::TodoError;
+use crate::task::TaskEdit;
+use crate::todo::TodoList;
+
+// 优先使用 $VISUAL，其次 $EDITOR，都没有时使用系统默认编辑器
+fn editor_command() -> String {
+    env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        })
+}
+
+// 临时文件放在只有自己能访问的目录中；有 $XDG_RUNTIME_DIR 时优先使用，通常在内存中，
+// 加密存储时备注的明文不会写到磁盘上
+fn private_dir() -> Result<tempfile::TempDir, TodoError> {
+    let base = env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(env::temp_dir);
+    let mut builder = tempfile::Builder::new();
+    builder.prefix("todo_list_");
+    #[cfg(unix)]
+    builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o700));
+    Ok(builder.tempdir_in(base)?)
+}
+
+// 在外部编辑器中编辑一段文本，返回编辑后的内容
+pub fn edit_text(name: &str, initial: &str) -> Result<String, TodoError> {
+    // 目录和文件随函数返回一起删除，出错时也一样
+    let dir = private_dir()?;
+    let mut file = tempfile::Builder::new()
+        .prefix(&format!("{}_", name))
+        .suffix(".md")
+        .tempfile_in(dir.path())?;
+    file.write_all(initial.as_bytes())?;
+    file.flush()?;
+    let path = file.path();
+
+    // 编辑器命令可能带参数，例如 "code --wait"
+    let editor = editor_command();
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| TodoError::Editor(format!("{}: {}", program, e)))?;
+    if !status.success() {
+        return Err(TodoError::Editor(format!("{} 退出码 {}", program, status)));
+    }
+    // 编辑器可能替换了文件，按路径重新读取
+    Ok(fs::read_to_string(path)?.trim_end().to_string())
+}
+
+// 在编辑器中修改任务备注
+pub fn edit_notes(todo: &mut TodoList, id: usize) -> Result<(), TodoError> {
+    let task = todo.get(id).ok_or(TodoError::InvalidIndex)?;
+    let notes = edit_text(&format!("task{}", id), &task.notes)?;
+    let edit = TaskEdit {
+        notes: Some(notes),
+        ..Default::default()
+    };
+    todo.edit_task(id, edit)
+}
+
+// 用 shell 脚本充当编辑器
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+
+    use super::*;
+
+    // 编辑器通过环境变量指定，只在这一个测试中设置，避免和其他测试互相影响
+    fn use_editor(script: &Path, body: &str) {
+        fs::write(script, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(script, fs::Permissions::from_mode(0o755)).unwrap();
+        unsafe { env::set_var("VISUAL", script) };
+    }
+
+    #[test]
+    fn notes_are_edited_in_a_private_file_that_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("editor.sh");
+        let log = dir.path().join("log");
+        // 记下文件路径、文件和目录的权限，然后修改内容
+        use_editor(
+            &script,
+            &format!(
+                "echo \"$1\" > {0}; stat -c %a \"$1\" \"$(dirname \"$1\")\" >> {0}\n\
+                 sed -i 's/旧/新/' \"$1\"; printf '\\n补充一行\\n\\n' >> \"$1\"",
+                log.display()
+            ),
+        );
+
+        let mut todo = TodoList::new();
+        todo.add_task("写周报".to_string(), "medium").unwrap();
+        todo.tasks.update(1, |task| task.notes = "旧的备注".to_string());
+        edit_notes(&mut todo, 1).unwrap();
+        assert_eq!(todo.get(1).unwrap().notes, "新的备注\n补充一行");
+
+        let log = fs::read_to_string(&log).unwrap();
+        let lines: Vec<&str> = log.lines().collect();
+        let path = Path::new(lines[0]);
+        assert_eq!(&lines[1..], ["600", "700"]);
+        assert!(!path.exists());
+        assert!(!path.parent().unwrap().exists());
+
+        // 编辑器失败时报错，临时文件同样删除
+        use_editor(&script, &format!("echo \"$1\" > {}; exit 3", dir.path().join("failed").display()));
+        assert!(matches!(edit_text("task1", "内容"), Err(TodoError::Editor(_))));
+        let failed = fs::read_to_string(dir.path().join("failed")).unwrap();
+        assert!(!Path::new(failed.trim()).exists());
+
+        assert!(matches!(edit_notes(&mut todo, 99), Err(TodoError::InvalidIndex)));
+    }
+}
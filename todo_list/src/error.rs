@@ -0,0 +1,114 @@
+use std::fmt;
+
+use crate::task::Status;
+
+// 自定义错误类型
+#[derive(Debug)]
+pub enum TodoError {
+    InvalidIndex,
+    NoTask,
+    NoCompletedTask,
+    InvalidPriority(String),
+    InvalidStatus(String),
+    InvalidTransition(Status, Status),
+    InvalidDate(String),
+    InvalidDependency(usize),
+    InvalidParent(usize),
+    InvalidSort(String),
+    InvalidEstimate(String),
+    InvalidFilter(String),
+    NoView(String),
+    NoTemplate(String),
+    Template(String),
+    Editor(String),
+    Config(String),
+    Git(String),
+    WrongPassphrase,
+    Crypto(String),
+    Hook(String),
+    Script(String),
+    Markdown(String),
+    Taskwarrior(String),
+    Sync(String),
+    Report(String),
+    Gui(String),
+    NotInteractive(String),
+    BatchFailed(usize),
+    HookRejected(String),
+    Io(String),
+    Parse(String),
+}
+
+impl fmt::Display for TodoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TodoError::InvalidIndex => write!(f, "任务编号无效！"),
+            TodoError::NoTask => write!(f, "没有任务"),
+            TodoError::NoCompletedTask => write!(f, "没有已完成的任务可以删除！"),
+            TodoError::InvalidPriority(p) => write!(f, "无效的优先级{}，请使用 high/medium/low", p),
+            TodoError::InvalidStatus(s) => write!(
+                f,
+                "无效的状态{}，请使用 todo/in-progress/waiting/blocked/done/cancelled",
+                s
+            ),
+            TodoError::InvalidTransition(from, to) => write!(
+                f,
+                "不能从「{}」切换到「{}」",
+                from.to_string(),
+                to.to_string()
+            ),
+            TodoError::InvalidDate(d) => write!(
+                f,
+                "无效的日期{}，请使用 2024-05-01、2024-05-01 18:00、today、tomorrow、+3d 或 +2w",
+                d
+            ),
+            TodoError::InvalidDependency(id) => write!(f, "任务不能依赖不存在的任务或自身：{}", id),
+            TodoError::InvalidParent(id) => write!(f, "父任务不存在，或不能是任务自身及其子任务：{}", id),
+            TodoError::InvalidEstimate(e) => {
+                write!(f, "无效的预估：{}，可用 90、90m、1.5h 或 3pt", e)
+            }
+            TodoError::InvalidSort(k) => write!(
+                f,
+                "无效的排序字段{}，可用 id/priority/status/due/created/description/urgency，前缀 - 表示倒序",
+                k
+            ),
+            TodoError::InvalidFilter(t) => write!(
+                f,
+                "无效的查询条件{}，可用 priority:/status:/due:/tag:/project:/parent:/blocked:、+标签、-标签和关键词",
+                t
+            ),
+            TodoError::NoView(name) => write!(f, "没有名为 '{}' 的视图", name),
+            TodoError::NoTemplate(name) => write!(f, "没有名为 '{}' 的模板", name),
+            TodoError::Template(e) => write!(f, "模板有误：{}", e),
+            TodoError::Editor(e) => write!(f, "打开编辑器失败：{}", e),
+            TodoError::Config(e) => write!(f, "配置错误：{}", e),
+            TodoError::Git(e) => write!(f, "git 操作失败：{}", e),
+            TodoError::WrongPassphrase => write!(f, "口令错误，或数据文件已损坏"),
+            TodoError::Crypto(e) => write!(f, "加密数据失败：{}", e),
+            TodoError::Hook(e) => write!(f, "运行钩子失败：{}", e),
+            TodoError::HookRejected(e) => write!(f, "钩子拒绝了这次操作：{}", e),
+            TodoError::Script(e) => write!(f, "无法解析命令：{}", e),
+            TodoError::Markdown(e) => write!(f, "无法导入 Markdown：{}", e),
+            TodoError::Taskwarrior(e) => write!(f, "Taskwarrior 数据有误：{}", e),
+            TodoError::Sync(e) => write!(f, "同步失败：{}", e),
+            TodoError::Report(e) => write!(f, "无法生成报告：{}", e),
+            TodoError::Gui(e) => write!(f, "图形界面出错：{}", e),
+            TodoError::NotInteractive(hint) => write!(f, "当前不是交互式终端，无法询问：{}", hint),
+            TodoError::BatchFailed(n) => write!(f, "批处理中有 {} 条命令失败", n),
+            TodoError::Io(e) => write!(f, "读写数据文件失败：{}", e),
+            TodoError::Parse(e) => write!(f, "解析数据文件失败：{}", e),
+        }
+    }
+}
+
+impl From<std::io::Error> for TodoError {
+    fn from(e: std::io::Error) -> Self {
+        TodoError::Io(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for TodoError {
+    fn from(e: serde_json::Error) -> Self {
+        TodoError::Parse(e.to_string())
+    }
+}
@@ -0,0 +1,327 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::TodoError;
+use crate::table::{Align, Cell, Overflow, Table};
+use crate::task::{Priority, Task};
+use crate::todo::TodoList;
+
+// 工作量预估：分钟数或故事点
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Estimate {
+    Minutes(u32),
+    Points(f64),
+}
+
+impl Estimate {
+    // 解析预估：90、90m、1.5h、3pt、3sp
+    pub fn parse(s: &str) -> Result<Estimate, TodoError> {
+        let s = s.trim().to_lowercase();
+        let invalid = || TodoError::InvalidEstimate(s.clone());
+        let split = s.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        let number: f64 = number.parse().map_err(|_| invalid())?;
+        if !number.is_finite() || number < 0.0 {
+            return Err(invalid());
+        }
+        // 分钟数超出 u32 时报错，不截断
+        let minutes = |minutes: f64| match minutes.round() {
+            m if m <= u32::MAX as f64 => Ok(Estimate::Minutes(m as u32)),
+            _ => Err(invalid()),
+        };
+        match unit.trim() {
+            "" | "m" | "min" | "分钟" => minutes(number),
+            "h" | "小时" => minutes(number * 60.0),
+            "pt" | "sp" | "点" => Ok(Estimate::Points(number)),
+            _ => Err(invalid()),
+        }
+    }
+
+    // 换算成分钟
+    pub fn minutes(self, minutes_per_point: f64) -> f64 {
+        match self {
+            Estimate::Minutes(minutes) => minutes as f64,
+            Estimate::Points(points) => points * minutes_per_point,
+        }
+    }
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Estimate::Minutes(minutes) => write!(f, "{}", format_minutes(*minutes as f64)),
+            Estimate::Points(points) => write!(f, "{}pt", points),
+        }
+    }
+}
+
+// 时间预算只接受时长，不接受故事点
+pub fn parse_budget(s: &str) -> Result<f64, TodoError> {
+    match Estimate::parse(s)? {
+        Estimate::Minutes(minutes) => Ok(minutes as f64),
+        Estimate::Points(_) => Err(TodoError::InvalidEstimate(s.to_string())),
+    }
+}
+
+// 显示时长，例如 2h30m
+pub fn format_minutes(minutes: f64) -> String {
+    let minutes = minutes.round() as u64;
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{}m", h, m),
+    }
+}
+
+// 计划设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Plan {
+    // 一天可用的时间，单位为分钟
+    pub budget: f64,
+    // 一个故事点折合多少分钟
+    pub minutes_per_point: f64,
+}
+
+impl Default for Plan {
+    fn default() -> Self {
+        Plan {
+            budget: 360.0,
+            minutes_per_point: 60.0,
+        }
+    }
+}
+
+// 一组任务的剩余工作量
+#[derive(Default)]
+struct Effort {
+    tasks: usize,
+    unestimated: usize,
+    // 每个预估最多 u32::MAX 分钟，合计用 u64 不会溢出
+    minutes: u64,
+    points: f64,
+}
+
+impl Effort {
+    fn add(&mut self, task: &Task) {
+        self.tasks += 1;
+        match task.estimate {
+            Some(Estimate::Minutes(minutes)) => self.minutes += u64::from(minutes),
+            Some(Estimate::Points(points)) => self.points += points,
+            None => self.unestimated += 1,
+        }
+    }
+
+    fn cells(&self, name: String, minutes_per_point: f64) -> Vec<Cell> {
+        let total = self.minutes as f64 + self.points * minutes_per_point;
+        vec![
+            name.into(),
+            self.tasks.to_string().into(),
+            self.unestimated.to_string().into(),
+            format_minutes(self.minutes as f64).into(),
+            format!("{}pt", self.points).into(),
+            format_minutes(total).into(),
+        ]
+    }
+}
+
+impl TodoList {
+    fn effort_table(&self, group: &str) -> Table<'_> {
+        let cfg = &self.config;
+        Table::new(cfg)
+            .column(group, Align::Left, Overflow::Truncate)
+            .column(cfg.tr("任务", "Tasks"), Align::Right, Overflow::Fixed)
+            .column(cfg.tr("未预估", "Unestimated"), Align::Right, Overflow::Fixed)
+            .column(cfg.tr("分钟", "Minutes"), Align::Right, Overflow::Fixed)
+            .column(cfg.tr("故事点", "Points"), Align::Right, Overflow::Fixed)
+            .column(cfg.tr("合计", "Total"), Align::Right, Overflow::Fixed)
+    }
+
+    // 未结束任务的剩余工作量，按优先级和项目汇总；故事点按配置折算成时间计入合计
+    pub fn show_effort(&self) -> Result<(), TodoError> {
+        let open: Vec<&Task> = self.tasks.iter().filter(|t| !t.status.is_closed()).collect();
+        if open.is_empty() {
+            return Err(TodoError::NoTask);
+        }
+        let cfg = &self.config;
+        let per_point = cfg.plan.minutes_per_point;
+
+        println!("\n{}{}", cfg.icon("⏱️ "), cfg.tr("剩余工作量（按优先级）：", "Remaining effort by priority:"));
+        let mut table = self.effort_table(cfg.tr("优先级", "Priority"));
+        let mut total = Effort::default();
+        for priority in [Priority::High, Priority::Medium, Priority::Low] {
+            let mut effort = Effort::default();
+            for task in open.iter().filter(|t| t.priority == priority) {
+                effort.add(task);
+                total.add(task);
+            }
+            table.row(effort.cells(cfg.priority_text(priority), per_point));
+        }
+        table.row(total.cells(cfg.tr("全部", "All").to_string(), per_point));
+        table.print();
+
+        println!("\n{}{}", cfg.icon("⏱️ "), cfg.tr("剩余工作量（按项目）：", "Remaining effort by project:"));
+        let mut projects: Vec<Option<&str>> = open.iter().map(|t| t.project.as_deref()).collect();
+        projects.sort();
+        projects.dedup();
+        let mut table = self.effort_table(cfg.tr("项目", "Project"));
+        for project in projects {
+            let mut effort = Effort::default();
+            for task in open.iter().filter(|t| t.project.as_deref() == project) {
+                effort.add(task);
+            }
+            let name = project.unwrap_or(cfg.tr("（无项目）", "(none)")).to_string();
+            table.row(effort.cells(name, per_point));
+        }
+        table.print();
+        Ok(())
+    }
+
+    // 规划今天：在时间预算内按优先级、截止时间挑选未结束且没有被阻塞的任务；
+    // 放不下的任务跳过，继续尝试后面更小的任务
+    pub fn plan_day(&self, budget: Option<f64>) -> Result<(), TodoError> {
+        let cfg = &self.config;
+        let budget = budget.unwrap_or(cfg.plan.budget);
+        let per_point = cfg.plan.minutes_per_point;
+        let mut candidates: Vec<&Task> = self
+            .tasks
+            .iter()
+            .filter(|t| !t.status.is_closed() && !self.is_blocked(t))
+            .collect();
+        if candidates.is_empty() {
+            return Err(TodoError::NoTask);
+        }
+        candidates.sort_by_key(|t| (t.priority, t.due.is_none(), t.due, t.id));
+
+        let mut table = Table::new(cfg)
+            .column(cfg.tr("编号", "ID"), Align::Right, Overflow::Fixed)
+            .column(cfg.tr("优先级", "Priority"), Align::Left, Overflow::Fixed)
+            .column(cfg.tr("描述", "Description"), Align::Left, Overflow::Wrap)
+            .column(cfg.tr("截止", "Due"), Align::Left, Overflow::Fixed)
+            .column(cfg.tr("预估", "Estimate"), Align::Right, Overflow::Fixed)
+            .column(cfg.tr("累计", "Cumulative"), Align::Right, Overflow::Fixed);
+        let (mut used, mut planned, mut skipped, mut unestimated) = (0.0, 0, 0, 0);
+        for task in candidates {
+            let Some(estimate) = task.estimate else {
+                unestimated += 1;
+                continue;
+            };
+            let minutes = estimate.minutes(per_point);
+            if used + minutes > budget {
+                skipped += 1;
+                continue;
+            }
+            used += minutes;
+            planned += 1;
+            table.row(vec![
+                task.id.to_string().into(),
+                Cell::colored(cfg.priority_text(task.priority), cfg.priority_color(task.priority)),
+                task.description.clone().into(),
+                task.due.map(|due| cfg.format_due(due)).unwrap_or_default().into(),
+                estimate.to_string().into(),
+                format_minutes(used).into(),
+            ]);
+        }
+
+        println!("\n{}{}", cfg.icon("🗓️ "), cfg.tr("今日计划：", "Plan for today:"));
+        if planned == 0 {
+            println!("{}", cfg.tr("没有能放进时间预算的任务", "No task fits in the budget"));
+        } else {
+            table.print();
+        }
+        println!(
+            "{}: {} / {}，{}: {}",
+            cfg.tr("已安排", "Planned"),
+            format_minutes(used),
+            format_minutes(budget),
+            cfg.tr("剩余", "left"),
+            format_minutes(budget - used)
+        );
+        if skipped > 0 {
+            println!("{}: {}", cfg.tr("放不下的任务", "Tasks that don't fit"), skipped);
+        }
+        if unestimated > 0 {
+            println!(
+                "{}: {}（{}）",
+                cfg.tr("未预估的任务", "Unestimated tasks"),
+                unestimated,
+                cfg.tr("用 edit --estimate 设置预估", "set one with edit --estimate")
+            );
+        }
+        Ok(())
+    }
+
+    // 统计中显示剩余工作量合计
+    pub fn show_effort_stats(&self) {
+        let mut effort = Effort::default();
+        for task in self.tasks.iter().filter(|t| !t.status.is_closed()) {
+            effort.add(task);
+        }
+        if effort.tasks == effort.unestimated {
+            return;
+        }
+        let cfg = &self.config;
+        let total = effort.minutes as f64 + effort.points * cfg.plan.minutes_per_point;
+        println!("\n{}{}", cfg.icon("⏱️ "), cfg.tr("剩余工作量:", "Remaining effort:"));
+        println!("{}: {}", cfg.tr("合计", "Total"), format_minutes(total));
+        println!("{}: {}", cfg.tr("未预估的任务", "Unestimated tasks"), effort.unestimated);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_minutes_hours_and_points() {
+        assert_eq!(Estimate::parse("90").unwrap(), Estimate::Minutes(90));
+        assert_eq!(Estimate::parse(" 90m ").unwrap(), Estimate::Minutes(90));
+        assert_eq!(Estimate::parse("45min").unwrap(), Estimate::Minutes(45));
+        assert_eq!(Estimate::parse("1.5H").unwrap(), Estimate::Minutes(90));
+        assert_eq!(Estimate::parse("2小时").unwrap(), Estimate::Minutes(120));
+        assert_eq!(Estimate::parse("3pt").unwrap(), Estimate::Points(3.0));
+        assert_eq!(Estimate::parse("0.5sp").unwrap(), Estimate::Points(0.5));
+        assert_eq!(Estimate::parse("2 点").unwrap(), Estimate::Points(2.0));
+    }
+
+    #[test]
+    fn parse_rejects_bad_or_huge_values() {
+        for s in ["", "h", "-5m", "1.2.3h", "3d", "2h30m", "99999999999m", "9999999999h"] {
+            assert!(Estimate::parse(s).is_err(), "{}", s);
+        }
+        assert_eq!(Estimate::parse("4294967295").unwrap(), Estimate::Minutes(u32::MAX));
+    }
+
+    #[test]
+    fn budget_only_accepts_durations() {
+        assert_eq!(parse_budget("6h").unwrap(), 360.0);
+        assert!(parse_budget("3pt").is_err());
+    }
+
+    #[test]
+    fn format_minutes_uses_hours_and_minutes() {
+        assert_eq!(format_minutes(0.0), "0m");
+        assert_eq!(format_minutes(45.0), "45m");
+        assert_eq!(format_minutes(120.0), "2h");
+        assert_eq!(format_minutes(150.4), "2h30m");
+        assert_eq!(Estimate::Points(2.5).to_string(), "2.5pt");
+    }
+
+    #[test]
+    fn effort_sums_large_estimates_without_overflow() {
+        let mut task = Task::new(1, "很大的任务".to_string(), Priority::High);
+        task.estimate = Some(Estimate::Minutes(u32::MAX));
+        let mut effort = Effort::default();
+        effort.add(&task);
+        effort.add(&task);
+        task.estimate = Some(Estimate::Points(1.5));
+        effort.add(&task);
+        task.estimate = None;
+        effort.add(&task);
+        assert_eq!(effort.minutes, 2 * u64::from(u32::MAX));
+        assert_eq!(effort.points, 1.5);
+        assert_eq!((effort.tasks, effort.unestimated), (4, 1));
+    }
+}
@@ -0,0 +1,259 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+use crate::date;
+use crate::error::TodoError;
+use crate::task::{Priority, Status, Task};
+use crate::todo::TodoList;
+
+// 截止时间条件
+#[derive(Debug, Clone, PartialEq)]
+enum DueFilter {
+    None,
+    Any,
+    Overdue,
+    // 7 天内到期（含已逾期）
+    Week,
+    On(NaiveDate),
+}
+
+// 查询中的一个条件
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    Priority(Vec<Priority>),
+    Status(Vec<Status>),
+    Due(DueFilter),
+    // 标签条件保存为小写，和标签索引一样按 Unicode 规则忽略大小写
+    Tag(String),
+    NotTag(String),
+    Blocked(bool),
+    // 项目及其子项目
+    Project(String),
+    Parent(usize),
+    // 描述或备注中包含的关键词
+    Word(String),
+}
+
+// 查询条件，多个条件之间是“并且”的关系，例如
+// priority:high status:pending due:today +work 报告
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    source: String,
+    terms: Vec<Term>,
+}
+
+fn invalid(term: &str) -> TodoError {
+    TodoError::InvalidFilter(term.to_string())
+}
+
+fn parse_statuses(value: &str) -> Result<Vec<Status>, TodoError> {
+    let mut statuses = Vec::new();
+    for part in value.split(',') {
+        match part.to_lowercase().as_str() {
+            "pending" | "open" | "未结束" => {
+                statuses.extend(Status::ALL.into_iter().filter(|s| !s.is_closed()))
+            }
+            "closed" | "已结束" => statuses.extend(Status::ALL.into_iter().filter(|s| s.is_closed())),
+            _ => statuses.push(Status::from_str(part)?),
+        }
+    }
+    Ok(statuses)
+}
+
+fn parse_due(value: &str) -> Result<DueFilter, TodoError> {
+    Ok(match value.to_lowercase().as_str() {
+        "none" | "无" => DueFilter::None,
+        "any" | "有" => DueFilter::Any,
+        "overdue" | "逾期" => DueFilter::Overdue,
+        "week" | "本周" => DueFilter::Week,
+        _ => DueFilter::On(date::parse_due(value)?.date()),
+    })
+}
+
+fn parse_term(word: &str) -> Result<Term, TodoError> {
+    if let Some(tag) = word.strip_prefix('+').filter(|t| !t.is_empty()) {
+        return Ok(Term::Tag(tag.to_lowercase()));
+    }
+    if let Some(tag) = word.strip_prefix('-').filter(|t| !t.is_empty()) {
+        return Ok(Term::NotTag(tag.to_lowercase()));
+    }
+    let Some((key, value)) = word.split_once(':') else {
+        return Ok(Term::Word(word.to_lowercase()));
+    };
+    if value.is_empty() {
+        return Err(invalid(word));
+    }
+    match key.to_lowercase().as_str() {
+        "priority" | "p" | "优先级" => {
+            let priorities = value.split(',').map(Priority::from_str).collect::<Result<_, _>>()?;
+            Ok(Term::Priority(priorities))
+        }
+        "status" | "s" | "状态" => Ok(Term::Status(parse_statuses(value)?)),
+        "due" | "截止" => Ok(Term::Due(parse_due(value)?)),
+        "tag" | "t" | "标签" => Ok(Term::Tag(value.to_lowercase())),
+        "project" | "proj" | "项目" => Ok(Term::Project(value.to_string())),
+        "parent" | "父任务" => value
+            .trim_start_matches('#')
+            .parse()
+            .map(Term::Parent)
+            .map_err(|_| invalid(word)),
+        "blocked" | "阻塞" => match value.to_lowercase().as_str() {
+            "yes" | "true" | "是" => Ok(Term::Blocked(true)),
+            "no" | "false" | "否" => Ok(Term::Blocked(false)),
+            _ => Err(invalid(word)),
+        },
+        _ => Err(invalid(word)),
+    }
+}
+
+impl Filter {
+    // 解析查询，空查询匹配所有任务
+    pub fn parse(s: &str) -> Result<Filter, TodoError> {
+        let terms = s.split_whitespace().map(parse_term).collect::<Result<_, _>>()?;
+        Ok(Filter {
+            source: s.split_whitespace().collect::<Vec<_>>().join(" "),
+            terms,
+        })
+    }
+
+    // 可以直接用索引查出候选任务的条件
+    fn indexed_term(&self) -> Option<&Term> {
+        let find = |f: fn(&Term) -> bool| self.terms.iter().find(|term| f(term));
+        find(|term| matches!(term, Term::Tag(_)))
+            .or_else(|| find(|term| matches!(term, Term::Parent(_))))
+            .or_else(|| find(|term| matches!(term, Term::Priority(p) if p.len() == 1)))
+            .or_else(|| find(|term| matches!(term, Term::Status(s) if s.len() == 1)))
+    }
+
+    pub fn matches(&self, todo: &TodoList, task: &Task, now: NaiveDateTime) -> bool {
+        self.terms.iter().all(|term| match term {
+            Term::Priority(priorities) => priorities.contains(&task.priority),
+            Term::Status(statuses) => statuses.contains(&task.status),
+            Term::Due(due) => match (due, task.due) {
+                (DueFilter::None, d) => d.is_none(),
+                (DueFilter::Any, d) => d.is_some(),
+                (DueFilter::Overdue, Some(d)) => d < now && !task.status.is_closed(),
+                (DueFilter::Week, Some(d)) => d <= now + Duration::days(7),
+                (DueFilter::On(day), Some(d)) => d.date() == *day,
+                (_, None) => false,
+            },
+            Term::Tag(tag) => task.tags.iter().any(|t| t.to_lowercase() == *tag),
+            Term::NotTag(tag) => !task.tags.iter().any(|t| t.to_lowercase() == *tag),
+            Term::Blocked(blocked) => todo.is_blocked(task) == *blocked,
+            Term::Project(project) => task.project.as_deref().is_some_and(|p| {
+                p == project || p.strip_prefix(project.as_str()).is_some_and(|rest| rest.starts_with('/'))
+            }),
+            Term::Parent(parent) => task.parent == Some(*parent),
+            Term::Word(word) => {
+                task.description.to_lowercase().contains(word)
+                    || task.notes.to_lowercase().contains(word)
+            }
+        })
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl TodoList {
+    // 符合查询条件的任务
+    pub fn filter_tasks(&self, filter: &Filter) -> Vec<&Task> {
+        let now = date::now();
+        // 有标签、父任务或单个优先级/状态条件时先用索引缩小范围，结果仍按编号排列
+        let candidates: Box<dyn Iterator<Item = &Task>> = match filter.indexed_term() {
+            Some(Term::Tag(tag)) => Box::new(self.tasks.with_tag(tag)),
+            Some(Term::Parent(parent)) => Box::new(self.tasks.children(*parent)),
+            Some(Term::Priority(priorities)) => Box::new(self.tasks.with_priority(priorities[0])),
+            Some(Term::Status(statuses)) => Box::new(self.tasks.with_status(statuses[0])),
+            _ => Box::new(self.tasks.iter()),
+        };
+        candidates.filter(|task| filter.matches(self, task, now)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(s: &str) -> Vec<Term> {
+        Filter::parse(s).unwrap().terms
+    }
+
+    fn todo() -> TodoList {
+        let mut todo = TodoList::new();
+        for (description, priority, tags) in [
+            ("写周报", "high", vec!["Work"]),
+            ("Über den Ärger reden", "low", vec!["ÄRGER"]),
+            ("买牛奶", "medium", vec![]),
+        ] {
+            todo.add_task(description.to_string(), priority).unwrap();
+            let id = todo.next_id - 1;
+            todo.tasks.update(id, |task| task.tags = tags.into_iter().map(str::to_string).collect());
+        }
+        todo.tasks.update(1, |task| task.project = Some("公司/周报".to_string()));
+        todo
+    }
+
+    fn ids(todo: &TodoList, query: &str) -> Vec<usize> {
+        let filter = Filter::parse(query).unwrap();
+        todo.filter_tasks(&filter).iter().map(|task| task.id).collect()
+    }
+
+    #[test]
+    fn parse_reads_each_kind_of_term() {
+        assert_eq!(
+            terms("p:high,low +Work -Home tag:ÄRGER 报告"),
+            vec![
+                Term::Priority(vec![Priority::High, Priority::Low]),
+                Term::Tag("work".to_string()),
+                Term::NotTag("home".to_string()),
+                Term::Tag("ärger".to_string()),
+                Term::Word("报告".to_string()),
+            ]
+        );
+        assert_eq!(
+            terms("due:none 阻塞:是 parent:#3"),
+            vec![Term::Due(DueFilter::None), Term::Blocked(true), Term::Parent(3)]
+        );
+        let closed = Status::ALL.into_iter().filter(|s| s.is_closed()).collect();
+        assert_eq!(terms("status:closed"), vec![Term::Status(closed)]);
+        assert_eq!(terms("  "), vec![]);
+        assert_eq!(Filter::parse("  p:high   +a ").unwrap().to_string(), "p:high +a");
+    }
+
+    #[test]
+    fn parse_rejects_unknown_or_empty_terms() {
+        for query in ["p:", "color:red", "parent:x", "blocked:maybe", "p:urgent", "due:someday"] {
+            assert!(Filter::parse(query).is_err(), "{}", query);
+        }
+        // 单独的 + 和 - 按关键词处理
+        assert_eq!(terms("+ -"), vec![Term::Word("+".to_string()), Term::Word("-".to_string())]);
+    }
+
+    #[test]
+    fn tags_match_regardless_of_unicode_case() {
+        let todo = todo();
+        assert_eq!(ids(&todo, "+work"), vec![1]);
+        assert_eq!(ids(&todo, "+ärger"), vec![2]);
+        assert_eq!(ids(&todo, "+Ärger p:low"), vec![2]);
+        assert_eq!(ids(&todo, "-ärger"), vec![1, 3]);
+        assert_eq!(ids(&todo, "-WORK -Ärger"), vec![3]);
+    }
+
+    #[test]
+    fn other_terms_combine_with_and() {
+        let todo = todo();
+        assert_eq!(ids(&todo, ""), vec![1, 2, 3]);
+        assert_eq!(ids(&todo, "project:公司"), vec![1]);
+        assert_eq!(ids(&todo, "project:公"), Vec::<usize>::new());
+        assert_eq!(ids(&todo, "über"), vec![2]);
+        assert_eq!(ids(&todo, "p:high,medium"), vec![1, 3]);
+        assert_eq!(ids(&todo, "p:high 牛奶"), Vec::<usize>::new());
+        assert_eq!(ids(&todo, "due:none status:pending"), vec![1, 2, 3]);
+    }
+}
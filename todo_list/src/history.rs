@@ -0,0 +1,239 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+use crate::error::TodoError;
+use crate::task::{Status, Task};
+use crate::todo::TodoList;
+
+// 两个版本中内容不同的字段，修改时间除外
+fn changed_fields(old: &Task, new: &Task) -> Vec<String> {
+    let (Ok(Value::Object(before)), Ok(Value::Object(after))) = (serde_json::to_value(old), serde_json::to_value(new))
+    else {
+        return Vec::new();
+    };
+    let mut fields: Vec<String> = before
+        .keys()
+        .chain(after.keys().filter(|key| !before.contains_key(*key)))
+        .filter(|key| *key != "modified" && before.get(*key) != after.get(*key))
+        .cloned()
+        .collect();
+    fields.sort();
+    fields
+}
+
+// 描述单个任务的变化，没有变化时返回 None；redact 时不写出任务描述，修改时列出改动的字段名
+fn describe_task(old: Option<&Task>, new: Option<&Task>, redact: bool) -> Option<String> {
+    let mut fields = Vec::new();
+    let (verb, task) = match (old, new) {
+        (None, Some(new)) => ("add", new),
+        (Some(old), None) => ("delete", old),
+        (Some(old), Some(new)) if old.status != new.status => {
+            let verb = match new.status {
+                Status::Done => "complete",
+                Status::Cancelled => "cancel",
+                Status::InProgress => "start",
+                Status::Waiting => "wait",
+                Status::Blocked => "block",
+                Status::Todo if old.status.is_closed() => "reopen",
+                Status::Todo => "unblock",
+            };
+            (verb, new)
+        }
+        (Some(old), Some(new)) => {
+            if old == new {
+                return None;
+            }
+            fields = changed_fields(old, new);
+            ("edit", new)
+        }
+        (None, None) => return None,
+    };
+    let mut line = match redact {
+        true => format!("{} #{}", verb, task.id),
+        false => format!("{} #{} {}", verb, task.id, task.description),
+    };
+    if !fields.is_empty() {
+        line.push_str(&format!(" ({})", fields.join(", ")));
+    }
+    Some(line)
+}
+
+// 比较两个版本的任务列表，生成提交说明，例如 "complete #4 研究人类的诞生"
+// 数据加密时只写编号，避免任务内容以明文出现在 git 历史中
+pub fn describe_changes(old: Option<&TodoList>, new: &TodoList, redact: bool) -> String {
+    let mut changes = Vec::new();
+    if let Some(old) = old {
+        for task in &old.tasks {
+            changes.extend(describe_task(Some(task), new.get(task.id), redact));
+        }
+    }
+    for task in &new.tasks {
+        if old.and_then(|old| old.get(task.id)).is_none() {
+            changes.extend(describe_task(None, Some(task), redact));
+        }
+    }
+
+    match changes.len() {
+        0 => "update".to_string(),
+        1 => changes.remove(0),
+        n => format!("{} changes\n\n{}", n, changes.join("\n")),
+    }
+}
+
+// 在数据文件所在目录执行 git 命令
+fn git(dir: &Path, args: &[&str]) -> Result<Output, TodoError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| TodoError::Git(format!("无法运行 git：{}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(TodoError::Git(stderr.trim().to_string()));
+    }
+    Ok(output)
+}
+
+fn split_path(path: &Path) -> Result<(&Path, &str), TodoError> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let file = path
+        .file_name()
+        .and_then(|f| f.to_str())
+        .ok_or_else(|| TodoError::Git(format!("无效的数据文件路径：{}", path.display())))?;
+    Ok((dir, file))
+}
+
+// 同一目录下的几个文件
+fn split_paths<'a>(paths: &[&'a Path]) -> Result<(&'a Path, Vec<&'a str>), TodoError> {
+    let mut dir = Path::new(".");
+    let mut files = Vec::new();
+    for path in paths {
+        let (d, file) = split_path(path)?;
+        dir = d;
+        files.push(file);
+    }
+    Ok((dir, files))
+}
+
+// 提交数据文件的当前内容，目录还不是 git 仓库时先初始化
+pub fn commit(paths: &[&Path], message: &str) -> Result<(), TodoError> {
+    let (dir, files) = split_paths(paths)?;
+    if !dir.join(".git").exists() {
+        git(dir, &["init", "--quiet"])?;
+    }
+    let with_files = |args: &[&'static str]| {
+        let mut args: Vec<&str> = args.to_vec();
+        args.push("--");
+        args.extend(&files);
+        args
+    };
+    git(dir, &with_files(&["add"]))?;
+
+    // 没有任何改动时不提交
+    if git(dir, &with_files(&["diff", "--cached", "--quiet"])).is_ok() {
+        return Ok(());
+    }
+    // 没有配置 git 用户时使用默认身份，避免提交失败
+    let mut args = Vec::new();
+    if git(dir, &["config", "user.email"]).is_err() {
+        args.extend(["-c", "user.name=todo_list", "-c", "user.email=todo_list@localhost"]);
+    }
+    args.extend(["commit", "--quiet", "-m", message, "--"]);
+    args.extend(&files);
+    git(dir, &args)?;
+    Ok(())
+}
+
+// 数据文件的提交历史
+pub fn log(paths: &[&Path], limit: usize) -> Result<String, TodoError> {
+    let (dir, files) = split_paths(paths)?;
+    if !dir.join(".git").exists() {
+        return Err(TodoError::Git("数据目录还没有 git 历史，请在配置中开启 storage.git".to_string()));
+    }
+    let limit = format!("-{}", limit);
+    let mut args = vec![
+        "log",
+        &limit,
+        "--date=format:%Y-%m-%d %H:%M",
+        "--format=%h  %ad  %s",
+        "--",
+    ];
+    args.extend(&files);
+    let output = git(dir, &args)?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+// 读取某个版本的数据文件内容
+pub fn show(path: &Path, rev: &str) -> Result<String, TodoError> {
+    let (dir, file) = split_path(path)?;
+    let output = git(dir, &["show", &format!("{}:./{}", rev, file)])?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TaskEdit;
+
+    fn lists() -> (TodoList, TodoList) {
+        let mut old = TodoList::new();
+        for description in ["写周报", "买牛奶", "修复登录", "读书"] {
+            old.add_task(description.to_string(), "medium").unwrap();
+        }
+        let mut new = old.clone();
+        new.add_task("新任务".to_string(), "high").unwrap();
+        new.tasks.remove(2);
+        new.edit_task(
+            3,
+            TaskEdit {
+                due: Some(Some(date_of("2024-05-01"))),
+                tags: Some(vec!["bug".to_string()]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        new.set_status(1, Status::Done).unwrap();
+        (old, new)
+    }
+
+    fn date_of(s: &str) -> chrono::NaiveDateTime {
+        crate::date::parse_due(s).unwrap()
+    }
+
+    #[test]
+    fn describe_changes_lists_added_removed_and_changed_tasks() {
+        let (old, new) = lists();
+        assert_eq!(
+            describe_changes(Some(&old), &new, false),
+            "4 changes\n\n\
+             complete #1 写周报\n\
+             delete #2 买牛奶\n\
+             edit #3 修复登录 (due, tags)\n\
+             add #5 新任务"
+        );
+        assert_eq!(
+            describe_changes(Some(&old), &new, true),
+            "4 changes\n\ncomplete #1\ndelete #2\nedit #3 (due, tags)\nadd #5"
+        );
+    }
+
+    #[test]
+    fn single_or_no_change() {
+        let (old, _) = lists();
+        assert_eq!(describe_changes(Some(&old), &old, false), "update");
+        let mut new = old.clone();
+        new.set_status(2, Status::InProgress).unwrap();
+        assert_eq!(describe_changes(Some(&old), &new, false), "start #2 买牛奶");
+        new.set_status(2, Status::Done).unwrap();
+        let mut reopened = new.clone();
+        reopened.set_status(2, Status::Todo).unwrap();
+        assert_eq!(describe_changes(Some(&new), &reopened, true), "reopen #2");
+        assert_eq!(describe_changes(None, &old, true), "4 changes\n\nadd #1\nadd #2\nadd #3\nadd #4");
+    }
+}
@@ -0,0 +1,261 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::error::TodoError;
+use crate::task::Task;
+
+// 触发钩子的任务事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    Add,
+    Complete,
+    Delete,
+    Edit,
+}
+
+impl HookEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::Add => "add",
+            HookEvent::Complete => "complete",
+            HookEvent::Delete => "delete",
+            HookEvent::Edit => "edit",
+        }
+    }
+}
+
+// 各事件要执行的命令，命令可以带参数，按顺序执行
+// 钩子从标准输入读到任务的 JSON；退出码非 0 表示拒绝这次操作，
+// 输出中以 { 开头的一行作为修改后的任务，其他行作为提示显示给用户
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    pub on_add: Vec<String>,
+    pub on_complete: Vec<String>,
+    pub on_delete: Vec<String>,
+    pub on_edit: Vec<String>,
+}
+
+impl Hooks {
+    fn commands(&self, event: HookEvent) -> &[String] {
+        match event {
+            HookEvent::Add => &self.on_add,
+            HookEvent::Complete => &self.on_complete,
+            HookEvent::Delete => &self.on_delete,
+            HookEvent::Edit => &self.on_edit,
+        }
+    }
+
+    // 依次执行事件的钩子，返回钩子修改后的任务；删除事件忽略钩子的修改
+    pub fn run(&self, event: HookEvent, task: Task) -> Result<Task, TodoError> {
+        let mut task = task;
+        for command in self.commands(event) {
+            let modified = run_hook(command, event, &task)?;
+            if let Some(mut modified) = modified
+                && event != HookEvent::Delete
+            {
+                // 编号由程序分配，钩子不能修改
+                modified.id = task.id;
+                task = modified;
+            }
+        }
+        Ok(task)
+    }
+}
+
+// 用户配置的外部命令（钩子、提醒命令），可以带参数，程序路径开头的 ~ 会展开
+pub struct UserCommand {
+    pub program: PathBuf,
+    args: Vec<String>,
+}
+
+impl UserCommand {
+    // 空命令返回 None
+    pub fn parse(command: &str) -> Option<UserCommand> {
+        let mut parts = command.split_whitespace();
+        let program = config::expand_home(Path::new(parts.next()?));
+        Some(UserCommand {
+            program,
+            args: parts.map(str::to_string).collect(),
+        })
+    }
+
+    // 执行命令并等待结束，任务的 JSON 写入标准输入；capture 时收集标准输出，否则直接显示
+    pub fn run(&self, envs: &[(&str, String)], task: &Task, capture: bool) -> io::Result<Output> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .envs(envs.iter().map(|(key, value)| (key, value)))
+            .stdin(Stdio::piped())
+            .stdout(if capture { Stdio::piped() } else { Stdio::inherit() })
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // 命令不读标准输入时写入会失败，不影响结果
+            let _ = writeln!(stdin, "{}", serde_json::to_string(task)?);
+        }
+        child.wait_with_output()
+    }
+}
+
+fn run_hook(command: &str, event: HookEvent, task: &Task) -> Result<Option<Task>, TodoError> {
+    let Some(command) = UserCommand::parse(command) else {
+        return Ok(None);
+    };
+    let program = &command.program;
+    let output = command
+        .run(&[("TODO_HOOK_EVENT", event.name().to_string())], task, true)
+        .map_err(|e| TodoError::Hook(format!("{}: {}", program.display(), e)))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut modified = None;
+    let mut feedback = Vec::new();
+    for line in stdout.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if line.starts_with('{') && modified.is_none() {
+            let task = serde_json::from_str(line).map_err(|e| {
+                TodoError::Hook(format!("{} 输出的任务无法解析：{}", program.display(), e))
+            })?;
+            modified = Some(task);
+        } else {
+            feedback.push(line);
+        }
+    }
+
+    if !output.status.success() {
+        let reason = match feedback.is_empty() {
+            true => format!("{} 退出码 {}", program.display(), output.status),
+            false => feedback.join("\n"),
+        };
+        return Err(TodoError::HookRejected(reason));
+    }
+    for line in feedback {
+        println!("🪝 {}", line);
+    }
+    Ok(modified)
+}
+
+// 用 shell 脚本充当钩子
+#[cfg(all(test, unix))]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+    use crate::task::{Priority, TaskEdit};
+    use crate::todo::TodoList;
+
+    // 在临时目录中写一个钩子脚本，返回配置中的命令
+    fn hook(dir: &Path, name: &str, body: &str) -> String {
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path.display().to_string()
+    }
+
+    fn task() -> Task {
+        Task::new(7, "原来的描述".to_string(), Priority::Medium)
+    }
+
+    #[test]
+    fn non_zero_exit_vetoes_the_operation() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = Hooks {
+            on_add: vec![hook(dir.path(), "veto.sh", "echo 不能在周末加任务; exit 1")],
+            ..Hooks::default()
+        };
+        match hooks.run(HookEvent::Add, task()) {
+            Err(TodoError::HookRejected(reason)) => assert_eq!(reason, "不能在周末加任务"),
+            other => panic!("{:?}", other),
+        }
+        // 没有提示时用退出码说明
+        let hooks = Hooks {
+            on_edit: vec![hook(dir.path(), "silent.sh", "exit 2")],
+            ..Hooks::default()
+        };
+        assert!(matches!(hooks.run(HookEvent::Edit, task()), Err(TodoError::HookRejected(_))));
+    }
+
+    #[test]
+    fn json_output_replaces_the_task_but_not_its_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = r#"read task; echo 已改写; echo "$task" | sed -e 's/原来的描述/钩子改的/' -e 's/"id":7/"id":99/'"#;
+        let hooks = Hooks {
+            on_add: vec![
+                hook(dir.path(), "rewrite.sh", script),
+                hook(dir.path(), "event.sh", r#"read task; echo "$task" | sed "s/\"tags\":\[\]/\"tags\":[\"$TODO_HOOK_EVENT\",\"$1\"]/""#)
+                    + " 参数",
+            ],
+            ..Hooks::default()
+        };
+        let added = hooks.run(HookEvent::Add, task()).unwrap();
+        assert_eq!(added.description, "钩子改的");
+        assert_eq!(added.id, 7);
+        assert_eq!(added.tags, vec!["add", "参数"]);
+
+        // 删除事件忽略钩子的修改
+        let hooks = Hooks {
+            on_delete: hooks.on_add.clone(),
+            ..Hooks::default()
+        };
+        assert_eq!(hooks.run(HookEvent::Delete, task()).unwrap().description, "原来的描述");
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = Hooks {
+            on_add: vec![hook(dir.path(), "broken.sh", "echo '{\"id\": 1, '")],
+            ..Hooks::default()
+        };
+        assert!(matches!(hooks.run(HookEvent::Add, task()), Err(TodoError::Hook(_))));
+        let hooks = Hooks {
+            on_add: vec![dir.path().join("missing.sh").display().to_string()],
+            ..Hooks::default()
+        };
+        assert!(matches!(hooks.run(HookEvent::Add, task()), Err(TodoError::Hook(_))));
+    }
+
+    #[test]
+    fn other_output_is_feedback_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = Hooks {
+            on_complete: vec![hook(dir.path(), "chatty.sh", "cat > /dev/null; echo 干得好; echo; echo '[1, 2]'")],
+            ..Hooks::default()
+        };
+        let task = task();
+        assert_eq!(hooks.run(HookEvent::Complete, task.clone()).unwrap(), task);
+        assert!(UserCommand::parse("   ").is_none());
+    }
+
+    #[test]
+    fn references_set_by_hooks_are_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut todo = TodoList::new();
+        todo.add_task("第一个".to_string(), "medium").unwrap();
+        todo.add_task("第二个".to_string(), "medium").unwrap();
+        todo.tasks.update(2, |task| task.depends = vec![1]);
+
+        let set = |field: &str, value: &str| {
+            let sed = format!(r#"read task; echo "$task" | sed 's/"{}":[^,}}]*/"{}":{}/'"#, field, field, value);
+            hook(dir.path(), &format!("{}.sh", field), &sed)
+        };
+        todo.config.hooks.on_add = vec![set("depends", "[42]")];
+        assert!(matches!(todo.add_task("x".to_string(), "low"), Err(TodoError::InvalidDependency(42))));
+        // 没有父任务时 JSON 中没有这个字段，直接加上
+        todo.config.hooks.on_add = vec![hook(dir.path(), "parent.sh", r#"read task; echo "$task" | sed 's/^{/{"parent":42,/'"#)];
+        assert!(matches!(todo.add_task("x".to_string(), "low"), Err(TodoError::InvalidParent(42))));
+        assert_eq!(todo.tasks.len(), 2);
+
+        // 第二个依赖第一个，钩子不能再让第一个依赖第二个
+        todo.config.hooks.on_add = Vec::new();
+        todo.config.hooks.on_edit = vec![set("depends", "[2]")];
+        let edit = TaskEdit {
+            description: Some("改一下".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(todo.edit_task(1, edit), Err(TodoError::InvalidDependency(2))));
+        assert!(todo.get(1).unwrap().depends.is_empty());
+    }
+}
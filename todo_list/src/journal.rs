@@ -0,0 +1,68 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::date;
+use crate::task::Task;
+use crate::todo::TodoList;
+
+// 日志中的一条事件；事件记录的是修改后的状态，重复回放结果不变
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum Event {
+    // 添加或修改任务
+    Put { task: Box<Task> },
+    Delete { id: usize },
+    NextId { next_id: usize },
+}
+
+// 日志文件的一行
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub time: NaiveDateTime,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+impl Entry {
+    pub fn new(event: Event) -> Self {
+        Entry {
+            time: date::now(),
+            event,
+        }
+    }
+}
+
+// 比较两个版本，生成从 old 变到 new 的事件
+pub fn diff(old: &TodoList, new: &TodoList) -> Vec<Event> {
+    let mut events = Vec::new();
+    for task in &old.tasks {
+        if new.get(task.id).is_none() {
+            events.push(Event::Delete { id: task.id });
+        }
+    }
+    for task in &new.tasks {
+        let before = old.get(task.id).and_then(|t| serde_json::to_value(t).ok());
+        if before.is_none() || before != serde_json::to_value(task).ok() {
+            events.push(Event::Put { task: Box::new(task.clone()) });
+        }
+    }
+    if old.next_id != new.next_id {
+        events.push(Event::NextId {
+            next_id: new.next_id,
+        });
+    }
+    events
+}
+
+// 回放一条事件
+pub fn apply(todo: &mut TodoList, event: Event) {
+    match event {
+        Event::Put { task } => {
+            todo.tasks.insert(*task);
+        }
+        Event::Delete { id } => {
+            todo.tasks.remove(id);
+        }
+        Event::NextId { next_id } => todo.next_id = next_id,
+    }
+}
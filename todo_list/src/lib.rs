@@ -0,0 +1,33 @@
+// 各功能模块，命令行程序和性能测试共用
+pub mod batch;
+pub mod calendar;
+pub mod cli;
+pub mod config;
+pub mod crypto;
+pub mod date;
+pub mod dedupe;
+pub mod display;
+pub mod editor;
+pub mod error;
+pub mod estimate;
+pub mod filter;
+pub mod history;
+pub mod hooks;
+pub mod journal;
+pub mod markdown;
+pub mod menu;
+pub mod merge;
+pub mod pomodoro;
+pub mod remind;
+pub mod report;
+pub mod sort;
+pub mod storage;
+pub mod sync;
+pub mod table;
+pub mod task;
+pub mod tasks;
+pub mod template;
+pub mod taskwarrior;
+pub mod todo;
+pub mod urgency;
+pub mod view;
@@ -0,0 +1,329 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+
+use crate::date;
+use crate::error::TodoError;
+use crate::task::{self, Priority, Status, Task};
+use crate::todo::TodoList;
+
+// 标题的用法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Headings {
+    // 各级标题用 / 连起来作为项目，例如 周会/后端
+    Project,
+    // 每级标题作为一个标签
+    Tag,
+}
+
+impl FromStr for Headings {
+    type Err = TodoError;
+
+    fn from_str(s: &str) -> Result<Headings, TodoError> {
+        match s.to_lowercase().as_str() {
+            "project" | "项目" => Ok(Headings::Project),
+            "tag" | "标签" => Ok(Headings::Tag),
+            _ => Err(TodoError::Markdown(format!("无效的标题用法：{}，可用 project 或 tag", s))),
+        }
+    }
+}
+
+// 清单中的一个条目
+struct Item {
+    // 所在行号，从 1 开始
+    line: usize,
+    // 父条目在列表中的位置
+    parent: Option<usize>,
+    task: Task,
+}
+
+// 标题行：返回级别和标题
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    if level == 0 || level > 6 || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+// 清单条目：- [ ] 描述、* [x] 描述、1. [ ] 描述，返回是否已勾选和描述
+fn checkbox(line: &str) -> Option<(bool, &str)> {
+    let rest = match line.strip_prefix(['-', '*', '+']) {
+        Some(rest) => rest,
+        None => {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            if digits == 0 {
+                return None;
+            }
+            line[digits..].strip_prefix(['.', ')'])?
+        }
+    };
+    let rest = rest.strip_prefix([' ', '\t'])?.trim_start();
+    let (done, rest) = if let Some(rest) = rest.strip_prefix("[ ]") {
+        (false, rest)
+    } else if let Some(rest) = rest.strip_prefix("[x]").or_else(|| rest.strip_prefix("[X]")) {
+        (true, rest)
+    } else {
+        return None;
+    };
+    if !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    Some((done, rest.trim()))
+}
+
+// 缩进宽度，制表符按 4 格算
+fn indent(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+// 从描述中取出 !high、#标签、@截止时间 这些标记，无法识别的保留在描述里
+fn apply_markers(task: &mut Task, text: &str) {
+    let mut words = Vec::new();
+    for word in text.split_whitespace() {
+        if let Some(priority) = word.strip_prefix('!').and_then(|p| Priority::from_str(p).ok()) {
+            task.priority = priority;
+        } else if let Some(tag) = word.strip_prefix('#').filter(|t| !t.is_empty()) {
+            for tag in task::parse_tags(tag) {
+                if !task.tags.contains(&tag) {
+                    task.tags.push(tag);
+                }
+            }
+        } else if let Some(due) = word.strip_prefix('@').and_then(|d| date::parse_due(d).ok()) {
+            task.due = Some(due);
+        } else {
+            words.push(word);
+        }
+    }
+    task.description = words.join(" ");
+}
+
+// 解析 Markdown 中的清单条目，代码块中的内容会被忽略；已勾选的条目以 now 作为完成时间
+fn parse(content: &str, headings: Headings, priority: Priority, now: NaiveDateTime) -> Result<Vec<Item>, TodoError> {
+    let mut items: Vec<Item> = Vec::new();
+    // 当前所在的各级标题
+    let mut titles: Vec<(usize, String)> = Vec::new();
+    // 外层条目的缩进和位置
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let mut in_code = false;
+
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        if let Some((level, title)) = heading(trimmed) {
+            titles.retain(|&(l, _)| l < level);
+            if !title.is_empty() {
+                titles.push((level, title.to_string()));
+            }
+            stack.clear();
+            continue;
+        }
+        let Some((done, text)) = checkbox(trimmed) else {
+            continue;
+        };
+
+        let depth = indent(line);
+        while stack.last().is_some_and(|&(d, _)| d >= depth) {
+            stack.pop();
+        }
+        let mut task = Task::new(0, String::new(), priority);
+        match headings {
+            Headings::Project if !titles.is_empty() => {
+                let path: Vec<&str> = titles.iter().map(|(_, t)| t.as_str()).collect();
+                task.project = Some(path.join("/"));
+            }
+            Headings::Project => {}
+            Headings::Tag => {
+                for (_, title) in &titles {
+                    let tag = title.split_whitespace().collect::<Vec<_>>().join("-");
+                    if !task.tags.contains(&tag) {
+                        task.tags.push(tag);
+                    }
+                }
+            }
+        }
+        apply_markers(&mut task, text);
+        if task.description.is_empty() {
+            return Err(TodoError::Markdown(format!("第 {} 行的条目没有描述", i + 1)));
+        }
+        if done {
+            task.status = Status::Done;
+            task.completed = Some(now);
+            task.modified = Some(now);
+        }
+        items.push(Item {
+            line: i + 1,
+            parent: stack.last().map(|&(_, index)| index),
+            task,
+        });
+        stack.push((depth, items.len() - 1));
+    }
+    Ok(items)
+}
+
+// 导入 Markdown 清单，dry_run 时只预览不修改；被钩子拒绝的条目会跳过，其子任务改为普通任务
+pub fn import(todo: &mut TodoList, path: &Path, headings: Headings, dry_run: bool) -> Result<(), TodoError> {
+    let content = fs::read_to_string(path)?;
+    let items = parse(&content, headings, todo.config.default_priority, date::now())?;
+    if items.is_empty() {
+        println!("📭 {} 中没有找到 - [ ] 清单条目", path.display());
+        return Ok(());
+    }
+    if dry_run {
+        // 预览时按依次添加计算编号
+        let tasks: Vec<Task> = items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| Task {
+                id: todo.next_id + i,
+                parent: item.parent.map(|p| todo.next_id + p),
+                ..item.task.clone()
+            })
+            .collect();
+        todo.preview_tasks(&tasks);
+        println!("\n🔍 预览：将从 {} 导入 {} 个任务，去掉 --dry-run 后执行导入", path.display(), items.len());
+        return Ok(());
+    }
+
+    let mut ids: Vec<Option<usize>> = Vec::new();
+    for item in items {
+        let mut task = item.task;
+        task.parent = item.parent.and_then(|index| ids[index]);
+        if item.parent.is_some() && task.parent.is_none() {
+            println!("⚠️ 第 {} 行的父任务没有导入，作为普通任务导入", item.line);
+        }
+        match todo.insert_task(task) {
+            Ok(id) => ids.push(Some(id)),
+            Err(e) => {
+                println!("🙅‍♂️第 {} 行：{}", item.line, e);
+                ids.push(None);
+            }
+        }
+    }
+    let imported = ids.iter().flatten().count();
+    println!("📥 已从 {} 导入 {} 个任务", path.display(), imported);
+    if imported < ids.len() {
+        println!("⚠️ 跳过 {} 个条目", ids.len() - imported);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTES: &str = "\
+# 周会
+随便写的一段话
+- [ ] 写周报 !high #工作
+  - [x] 收集数据
+  - [ ] 画图表 @2024-05-10
+- 普通列表项
+## 后端
+1. [ ] 修复登录 #bug,紧急
+```
+- [ ] 代码块中的条目
+```
+# 
+* [X] 没有标题的条目
+";
+
+    fn now() -> NaiveDateTime {
+        date::parse_due("2024-05-08 10:00").unwrap()
+    }
+
+    #[test]
+    fn heading_and_checkbox_lines() {
+        assert_eq!(heading("## 后端 ##"), Some((2, "后端")));
+        assert_eq!(heading("#"), Some((1, "")));
+        assert_eq!(heading("#标签"), None);
+        assert_eq!(heading("####### 太深"), None);
+        assert_eq!(checkbox("- [ ] 写周报"), Some((false, "写周报")));
+        assert_eq!(checkbox("+ [X] 完成"), Some((true, "完成")));
+        assert_eq!(checkbox("12) [x] 编号"), Some((true, "编号")));
+        assert_eq!(checkbox("- [ ]"), Some((false, "")));
+        assert_eq!(checkbox("- [ ]没有空格"), None);
+        assert_eq!(checkbox("-[ ] 没有空格"), None);
+        assert_eq!(checkbox("- 普通列表项"), None);
+        assert_eq!(indent("\t  - [ ] x"), 6);
+    }
+
+    #[test]
+    fn parse_builds_projects_and_subtasks() {
+        let items = parse(NOTES, Headings::Project, Priority::Medium, now()).unwrap();
+        let summary: Vec<(usize, Option<usize>, &str, Option<&str>)> = items
+            .iter()
+            .map(|item| (item.line, item.parent, item.task.description.as_str(), item.task.project.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (3, None, "写周报", Some("周会")),
+                (4, Some(0), "收集数据", Some("周会")),
+                (5, Some(0), "画图表", Some("周会")),
+                (8, None, "修复登录", Some("周会/后端")),
+                (13, None, "没有标题的条目", None),
+            ]
+        );
+        assert_eq!(items[0].task.priority, Priority::High);
+        assert_eq!(items[0].task.tags, vec!["工作"]);
+        assert_eq!(items[1].task.status, Status::Done);
+        assert_eq!(items[1].task.completed, Some(now()));
+        assert_eq!(items[1].task.modified, Some(now()));
+        assert_eq!(items[0].task.completed, None);
+        assert_eq!(items[2].task.due, Some(date::parse_due("2024-05-10").unwrap()));
+        assert_eq!(items[3].task.priority, Priority::Medium);
+        assert_eq!(items[3].task.tags, vec!["bug", "紧急"]);
+        assert_eq!(items[4].task.status, Status::Done);
+    }
+
+    #[test]
+    fn parse_can_turn_headings_into_tags() {
+        let items = parse("# 周会 记录\n## 后端\n- [ ] 修复登录 #后端\n", Headings::Tag, Priority::Low, now()).unwrap();
+        assert_eq!(items[0].task.tags, vec!["周会-记录", "后端"]);
+        assert_eq!(items[0].task.project, None);
+        assert_eq!(items[0].task.priority, Priority::Low);
+    }
+
+    #[test]
+    fn unknown_markers_stay_in_the_description() {
+        let items = parse("- [ ] 回复 @someone !maybe #\n", Headings::Project, Priority::Medium, now()).unwrap();
+        assert_eq!(items[0].task.description, "回复 @someone !maybe #");
+        assert!(items[0].task.tags.is_empty());
+    }
+
+    #[test]
+    fn parse_rejects_items_without_description() {
+        let result = parse("text\n- [ ] #只有标签\n", Headings::Project, Priority::Medium, now());
+        assert!(matches!(result, Err(TodoError::Markdown(message)) if message.contains("第 2 行")));
+        assert_eq!("标签".parse::<Headings>().unwrap(), Headings::Tag);
+        assert!("title".parse::<Headings>().is_err());
+    }
+
+    #[test]
+    fn imported_done_items_count_as_completed_today() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.md");
+        fs::write(&path, "- [x] 已经做完\n  - [ ] 还没做\n").unwrap();
+        let mut todo = TodoList::new();
+        import(&mut todo, &path, Headings::Project, false).unwrap();
+
+        let done = todo.get(1).unwrap();
+        assert_eq!(done.status, Status::Done);
+        assert_eq!(done.completed.map(|c| c.date()), Some(date::today()));
+        assert_eq!(todo.get(2).unwrap().parent, Some(1));
+        assert_eq!(todo.get(2).unwrap().completed, None);
+    }
+}
@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+use std::io::{self};
+use std::process;
+use std::str::FromStr;
+
+use chrono::Datelike;
+
+use crate::calendar;
+use crate::date;
+use crate::dedupe::{self, OnDuplicate};
+use crate::editor;
+use crate::error::TodoError;
+use crate::estimate;
+use crate::pomodoro;
+use crate::sort::SortSpec;
+use crate::storage::Store;
+use crate::task::{self, Priority, Status, TaskEdit};
+use crate::todo::TodoList;
+
+// 保存任务列表，失败时只提示不退出
+fn save(todo: &mut TodoList, store: &mut Store) {
+    if let Err(e) = store.save(todo) {
+        println!("🙅‍♂️{}", e);
+    }
+}
+
+// 获取用户输入；输入结束（例如管道读完）时退出，修改在每次操作后都已保存
+fn get_input(prompt: &str) -> String {
+    println!("{}", prompt);
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input).expect("读取输入失败") == 0 {
+        println!("👋 再见！感谢使用任务管理器！");
+        process::exit(0);
+    }
+    input.trim().to_string()
+}
+
+// 读取可选的截止时间和标签，留空表示不修改
+fn input_due_and_tags(edit: &mut TaskEdit) -> Result<(), TodoError> {
+    let due = get_input("请输入截止时间（2024-05-01 / today / +3d，输入 - 清除，留空跳过）:");
+    match due.as_str() {
+        "" => {}
+        "-" => edit.due = Some(None),
+        due => edit.due = Some(Some(date::parse_due(due)?)),
+    }
+    let tags = get_input("请输入标签（逗号分隔，输入 - 清除，留空跳过）:");
+    match tags.as_str() {
+        "" => {}
+        "-" => edit.tags = Some(Vec::new()),
+        tags => edit.tags = Some(task::parse_tags(tags)),
+    }
+    Ok(())
+}
+
+// 编辑任务的交互流程
+fn edit_task(todo: &mut TodoList, id: usize) -> Result<(), TodoError> {
+    let mut edit = TaskEdit::default();
+    let description = get_input("请输入新的任务描述（留空跳过）:");
+    if !description.is_empty() {
+        edit.description = Some(description);
+    }
+    let priority = get_input("请输入新的优先级（留空跳过）:");
+    if !priority.is_empty() {
+        edit.priority = Some(Priority::from_str(&priority)?);
+    }
+    input_due_and_tags(&mut edit)?;
+    let depends = get_input("请输入依赖的任务编号（逗号分隔，输入 - 清除，留空跳过）:");
+    match depends.as_str() {
+        "" => {}
+        "-" => edit.depends = Some(Vec::new()),
+        depends => edit.depends = Some(task::parse_ids(depends)?),
+    }
+    todo.edit_task(id, edit)
+}
+
+// 日历视图：翻页或查看某天的任务
+fn calendar(todo: &TodoList) {
+    let mut month = calendar::month_start(date::today());
+    loop {
+        todo.show_calendar(month);
+        let input = get_input("输入 n 下个月、p 上个月、日期数字查看当天任务，留空返回:");
+        match input.as_str() {
+            "" | "q" => break,
+            "n" => month = calendar::shift_month(month, 1),
+            "p" => month = calendar::shift_month(month, -1),
+            day => match day.parse::<u32>().ok().and_then(|d| month.with_day(d)) {
+                Some(day) => {
+                    if let Err(e) = todo.list_day(day) {
+                        println!("🙅‍♂️{}", e);
+                    }
+                    let _ = get_input("\n按回车键返回日历...");
+                }
+                None => println!("🙅‍♂️请输入有效日期！"),
+            },
+        }
+    }
+}
+
+// 保存的视图：查看、新建或删除
+fn views(todo: &TodoList) -> Result<(), TodoError> {
+    todo.list_views()?;
+    let input = get_input("输入视图编号查看，s 新建视图，d 删除视图，留空返回:");
+    let mut views = todo.load_views()?;
+    match input.as_str() {
+        "" => Ok(()),
+        "s" => {
+            let name = get_input("请输入视图名称：");
+            if name.is_empty() {
+                println!("🙅视图名称不能为空！");
+                return Ok(());
+            }
+            let query = get_input("请输入查询条件（如 priority:high status:pending due:today +标签 关键词）:");
+            let sort = get_input("请输入排序方式（留空按添加顺序）:");
+            views.set(&name, &query, &sort)
+        }
+        "d" => {
+            let name = get_input("请输入要删除的视图名称：");
+            views.remove(&name)
+        }
+        input => {
+            let view = input
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| views.views.get(i.wrapping_sub(1)))
+                .ok_or(TodoError::InvalidIndex)?;
+            todo.show_view(&view.name, &SortSpec::default())
+        }
+    }
+}
+
+// 选择模板，逐个询问变量，预览后确认创建
+fn templates(todo: &mut TodoList) -> Result<bool, TodoError> {
+    todo.list_templates()?;
+    let input = get_input("输入模板编号创建任务，留空返回:");
+    if input.is_empty() {
+        return Ok(false);
+    }
+    let templates = todo.load_templates()?;
+    let template = input
+        .parse::<usize>()
+        .ok()
+        .and_then(|i| templates.templates.get(i.wrapping_sub(1)))
+        .ok_or(TodoError::InvalidIndex)?;
+    let mut vars = HashMap::new();
+    for name in template.placeholders() {
+        vars.insert(name.clone(), get_input(&format!("请输入 {}：", name)));
+    }
+    let today = date::today();
+    todo.instantiate(template, &vars, today, true)?;
+    if !matches!(get_input("确认创建这些任务？(y/N)").to_lowercase().as_str(), "y" | "yes" | "是") {
+        println!("已取消");
+        return Ok(false);
+    }
+    todo.instantiate(template, &vars, today, false)?;
+    Ok(true)
+}
+
+// 显示菜单
+fn show_menu() {
+    println!("\n🎯 Rust任务管理器");
+    println!("=================");
+    println!("1. 添加任务");
+    println!("2. 查看所有任务");
+    println!("3. 按优先级查看任务");
+    println!("4. 完成任务");
+    println!("5. 删除任务");
+    println!("6. 删除所有已完成任务");
+    println!("7. 搜索任务");
+    println!("8. 查看统计");
+    println!("9. 修改任务状态");
+    println!("10. 看板视图");
+    println!("11. 编辑任务");
+    println!("12. 按紧急度查看任务");
+    println!("13. 四象限视图");
+    println!("14. 自定义排序查看任务");
+    println!("15. 查看任务详情");
+    println!("16. 编辑任务备注");
+    println!("17. 专注模式（番茄钟）");
+    println!("18. 日历视图");
+    println!("19. 保存的视图");
+    println!("20. 规划今天");
+    println!("21. 从模板创建任务");
+    println!("22. 查找重复任务");
+    println!("0. 退出");
+    println!("=================");
+}
+
+// 交互式菜单
+pub fn run(todo: &mut TodoList, store: &mut Store) {
+    println!("🚀 欢迎使用Rust任务管理器！");
+
+    loop {
+        show_menu();
+        let choice = get_input("请选择操作(0-22):");
+
+        match choice.as_str() {
+            "1" => {
+                let description = get_input("请输入任务描述：");
+                if description.is_empty() {
+                    println!("🙅任务描述不能为空！");
+                    continue;
+                }
+
+                let mut priority = get_input("请输入优先级（high/medium/low 或 h/m/l 或 1/2/3，留空使用默认值）:");
+                if priority.is_empty() {
+                    priority = todo.config.default_priority.to_english().to_string();
+                }
+                let mut edit = TaskEdit::default();
+                if let Err(e) = input_due_and_tags(&mut edit) {
+                    println!("🙅‍♂️{}", e);
+                    continue;
+                }
+                match dedupe::add_task(todo, description, &priority, edit, OnDuplicate::Ask) {
+                    Ok(true) => save(todo, store),
+                    Ok(false) => {}
+                    Err(e) => println!("🙅‍♂️{}", e),
+                }
+            },
+            "2" => {
+                if let Err(e) = todo.list_tasks(&SortSpec::default()) {
+                    println!("🙅‍♂️{}", e);
+                }
+            },
+            "3" => {
+                if let Err(e) = todo.list_task_by_priority() {
+                    println!("🙅‍♂️{}", e);
+                }
+            },
+            "4" => {
+                if todo.list_tasks(&SortSpec::default()).is_ok() {
+                    let input = get_input("请输入要完成的任务编号：");
+                    match input.parse::<usize>() {
+                        Ok(id) => match todo.complete_task(id) {
+                            Ok(_) => save(todo, store),
+                            Err(e) => println!("🙅‍♂️{}", e),
+                        },
+                        Err(_) => println!("🙅‍♂️请输入有效数字！"),
+                    }
+                }
+            },
+            "5" => {
+                if todo.list_tasks(&SortSpec::default()).is_ok() {
+                    let input = get_input("请输入要删除的任务编号：");
+                    match input.parse::<usize>() {
+                        Ok(id) => match todo.delete_task(id) {
+                            Ok(_) => save(todo, store),
+                            Err(e) => println!("🙅‍♂️{}", e),
+                        },
+                        Err(_) => println!("🙅‍♂️请输入有效数字！"),
+                    }
+                }
+            },
+            "6" => {
+                match todo.delete_complete_task() {
+                    Ok(_) => save(todo, store),
+                    Err(e) => println!("🙅‍♂️{}", e),
+                }
+            },
+            "7" => {
+                let keyword = get_input("请输入搜索关键字：");
+                if !keyword.is_empty() {
+                    let _ = todo.search_tasks(&keyword, &SortSpec::default());
+                }
+            },
+            "8" => {
+                todo.show_stats();
+            },
+            "9" => {
+                if todo.list_tasks(&SortSpec::default()).is_ok() {
+                    let input = get_input("请输入要修改状态的任务编号：");
+                    let Ok(id) = input.parse::<usize>() else {
+                        println!("🙅‍♂️请输入有效数字！");
+                        continue;
+                    };
+                    let status = get_input("请输入新状态（todo/in-progress/waiting/blocked/done/cancelled）:");
+                    match Status::from_str(&status).and_then(|status| todo.set_status(id, status)) {
+                        Ok(_) => save(todo, store),
+                        Err(e) => println!("🙅‍♂️{}", e),
+                    }
+                }
+            },
+            "10" => {
+                if let Err(e) = todo.list_kanban(&SortSpec::default()) {
+                    println!("🙅‍♂️{}", e);
+                }
+            },
+            "11" => {
+                if todo.list_tasks(&SortSpec::default()).is_ok() {
+                    let input = get_input("请输入要编辑的任务编号：");
+                    match input.parse::<usize>() {
+                        Ok(id) => match edit_task(todo, id) {
+                            Ok(_) => save(todo, store),
+                            Err(e) => println!("🙅‍♂️{}", e),
+                        },
+                        Err(_) => println!("🙅‍♂️请输入有效数字！"),
+                    }
+                }
+            },
+            "12" => {
+                if let Err(e) = todo.list_by_urgency() {
+                    println!("🙅‍♂️{}", e);
+                }
+            },
+            "13" => {
+                if let Err(e) = todo.show_matrix() {
+                    println!("🙅‍♂️{}", e);
+                }
+            },
+            "14" => {
+                let input = get_input("请输入排序方式（如 priority,-due,created，前缀 - 表示倒序）:");
+                if let Err(e) = SortSpec::parse(&input).and_then(|sort| todo.list_tasks(&sort)) {
+                    println!("🙅‍♂️{}", e);
+                }
+            },
+            "15" => {
+                let input = get_input("请输入要查看的任务编号：");
+                match input.parse::<usize>() {
+                    Ok(id) => {
+                        if let Err(e) = todo.show_task(id) {
+                            println!("🙅‍♂️{}", e);
+                        }
+                    }
+                    Err(_) => println!("🙅‍♂️请输入有效数字！"),
+                }
+            },
+            "16" => {
+                if todo.list_tasks(&SortSpec::default()).is_ok() {
+                    let input = get_input("请输入要编辑备注的任务编号：");
+                    match input.parse::<usize>() {
+                        Ok(id) => match editor::edit_notes(todo, id) {
+                            Ok(_) => save(todo, store),
+                            Err(e) => println!("🙅‍♂️{}", e),
+                        },
+                        Err(_) => println!("🙅‍♂️请输入有效数字！"),
+                    }
+                }
+            },
+            "17" => {
+                let input = get_input("请输入要专注的任务编号（留空自动选择最紧急的任务）：");
+                let id = match input.as_str() {
+                    "" => None,
+                    input => match input.parse::<usize>() {
+                        Ok(id) => Some(id),
+                        Err(_) => {
+                            println!("🙅‍♂️请输入有效数字！");
+                            continue;
+                        }
+                    },
+                };
+                if let Err(e) = pomodoro::focus(todo, store, id, None) {
+                    println!("🙅‍♂️{}", e);
+                }
+            },
+            "18" => {
+                calendar(todo);
+            },
+            "19" => {
+                if let Err(e) = views(todo) {
+                    println!("🙅‍♂️{}", e);
+                }
+            },
+            "20" => {
+                let input = get_input("请输入时间预算（例如 4h、90m，留空使用配置中的预算）：");
+                let budget = match input.as_str() {
+                    "" => Ok(None),
+                    input => estimate::parse_budget(input).map(Some),
+                };
+                if let Err(e) = budget.and_then(|budget| todo.plan_day(budget)) {
+                    println!("🙅‍♂️{}", e);
+                }
+            },
+            "21" => match templates(todo) {
+                Ok(true) => save(todo, store),
+                Ok(false) => {}
+                Err(e) => println!("🙅‍♂️{}", e),
+            },
+            "22" => match dedupe::dedupe(todo, false, false) {
+                Ok(0) => {}
+                Ok(_) => save(todo, store),
+                Err(e) => println!("🙅‍♂️{}", e),
+            },
+            "0" => {
+                println!("👋 再见！感谢使用任务管理器！");
+                break;
+            },
+            _ => {
+                println!("无效选择，请重新输入！");
+            }
+
+        }
+
+        // 按任意键继续
+        let _ = get_input("\n按回车键继续...");
+    }
+}
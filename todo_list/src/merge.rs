@@ -0,0 +1,247 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use serde_json::{Map, Value};
+
+use crate::task::Task;
+use crate::todo::TodoList;
+
+// 两个任务的内容是否相同
+fn same(a: Option<&Task>, b: Option<&Task>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => serde_json::to_value(a).ok() == serde_json::to_value(b).ok(),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+// 逐个字段三方合并：只有一方改动的字段取改动的一方，双方都改了同一个字段时以本进程为准
+fn merge_fields(base: &Task, ours: &Task, theirs: &Task) -> Task {
+    let fields = |task: &Task| match serde_json::to_value(task) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    };
+    let (base_fields, our_fields, their_fields) = (fields(base), fields(ours), fields(theirs));
+    let keys: BTreeSet<&String> = base_fields.keys().chain(our_fields.keys()).chain(their_fields.keys()).collect();
+    let mut merged = Map::new();
+    for key in keys {
+        // 没有的字段表示空值，例如去掉了截止时间
+        let ours = our_fields.get(key);
+        let value = if ours == base_fields.get(key) { their_fields.get(key) } else { ours };
+        if let Some(value) = value {
+            merged.insert(key.clone(), value.clone());
+        }
+    }
+    serde_json::from_value(Value::Object(merged)).unwrap_or_else(|_| ours.clone())
+}
+
+// 三方合并：base 是本进程读取时的版本，ours 是本进程修改后的版本，theirs 是磁盘上的最新版本
+// 双方修改同一个任务时逐个字段合并，只有同一个字段双方都改了时以本进程为准；
+// 一方删除、另一方修改时保留修改，避免丢失更新
+pub fn merge(base: Option<&TodoList>, ours: &mut TodoList, theirs: &TodoList) {
+    let empty = TodoList::new();
+    let base = base.unwrap_or(&empty);
+
+    // 本进程新加的任务，编号被其他进程占用时重新分配
+    let mut next_id = ours.next_id.max(theirs.next_id);
+    let mut renamed = HashMap::new();
+    for task in &ours.tasks {
+        if base.get(task.id).is_none() && theirs.get(task.id).is_some() {
+            println!("🔀 编号 #{} 已被其他进程使用，新任务改为 #{}", task.id, next_id);
+            renamed.insert(task.id, next_id);
+            next_id += 1;
+        }
+    }
+    // 取本进程的任务时一并更新编号和依赖
+    let mine = |task: &Task| {
+        let mut task = task.clone();
+        task.id = renamed.get(&task.id).copied().unwrap_or(task.id);
+        for dep in &mut task.depends {
+            if let Some(&id) = renamed.get(dep) {
+                *dep = id;
+            }
+        }
+        if let Some(parent) = task.parent.and_then(|parent| renamed.get(&parent)) {
+            task.parent = Some(*parent);
+        }
+        task
+    };
+
+    let mut tasks = Vec::new();
+    for task in &theirs.tasks {
+        match (base.get(task.id), ours.get(task.id)) {
+            // 其他进程新加的任务
+            (None, _) => tasks.push(task.clone()),
+            (Some(old), Some(changed)) => tasks.push(merge_fields(old, &mine(changed), task)),
+            // 本进程删除了、其他进程修改过的任务
+            (Some(old), None) if !same(Some(old), Some(task)) => tasks.push(task.clone()),
+            _ => {}
+        }
+    }
+    for task in &ours.tasks {
+        let old = base.get(task.id);
+        if old.is_none() {
+            tasks.push(mine(task));
+        } else if theirs.get(task.id).is_none() && !same(old, Some(task)) {
+            // 其他进程删除了、本进程修改过的任务
+            tasks.push(mine(task));
+        }
+    }
+
+    // 去掉指向已删除任务的依赖和父任务
+    let ids: HashSet<usize> = tasks.iter().map(|task| task.id).collect();
+    for task in &mut tasks {
+        task.depends.retain(|dep| ids.contains(dep));
+        if task.parent.is_some_and(|parent| !ids.contains(&parent)) {
+            task.parent = None;
+        }
+    }
+
+    ours.tasks = tasks.into_iter().collect();
+    ours.next_id = next_id;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Priority, Status};
+
+    fn base() -> TodoList {
+        let mut todo = TodoList::new();
+        todo.add_task("写周报".to_string(), "medium").unwrap();
+        todo.add_task("买牛奶".to_string(), "low").unwrap();
+        todo
+    }
+
+    fn edit(todo: &mut TodoList, id: usize, f: impl FnOnce(&mut Task)) {
+        todo.tasks.update(id, f);
+    }
+
+    #[test]
+    fn disjoint_field_edits_are_both_kept() {
+        let base = base();
+        let mut ours = base.clone();
+        edit(&mut ours, 1, |task| task.description = "写月报".to_string());
+        let mut theirs = base.clone();
+        edit(&mut theirs, 1, |task| {
+            task.priority = Priority::High;
+            task.tags = vec!["work".to_string()];
+        });
+
+        merge(Some(&base), &mut ours, &theirs);
+
+        let task = ours.get(1).unwrap();
+        assert_eq!(task.description, "写月报");
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.tags, vec!["work"]);
+    }
+
+    #[test]
+    fn same_field_edits_prefer_this_process() {
+        let base = base();
+        let mut ours = base.clone();
+        edit(&mut ours, 1, |task| task.description = "我们的".to_string());
+        let mut theirs = base.clone();
+        edit(&mut theirs, 1, |task| {
+            task.description = "他们的".to_string();
+            task.status = Status::Done;
+        });
+
+        merge(Some(&base), &mut ours, &theirs);
+
+        let task = ours.get(1).unwrap();
+        assert_eq!(task.description, "我们的");
+        assert_eq!(task.status, Status::Done);
+    }
+
+    #[test]
+    fn removed_optional_field_is_merged() {
+        let mut base = base();
+        edit(&mut base, 1, |task| task.project = Some("工作".to_string()));
+        let mut ours = base.clone();
+        edit(&mut ours, 1, |task| task.project = None);
+        let mut theirs = base.clone();
+        edit(&mut theirs, 1, |task| task.priority = Priority::High);
+
+        merge(Some(&base), &mut ours, &theirs);
+
+        let task = ours.get(1).unwrap();
+        assert_eq!(task.project, None);
+        assert_eq!(task.priority, Priority::High);
+    }
+
+    #[test]
+    fn edit_wins_over_delete() {
+        let base = base();
+        // 本进程删除，其他进程修改
+        let mut ours = base.clone();
+        ours.tasks.remove(1);
+        let mut theirs = base.clone();
+        edit(&mut theirs, 1, |task| task.description = "改过的".to_string());
+        merge(Some(&base), &mut ours, &theirs);
+        assert_eq!(ours.get(1).unwrap().description, "改过的");
+
+        // 其他进程删除，本进程修改
+        let mut ours = base.clone();
+        edit(&mut ours, 2, |task| task.description = "改过的".to_string());
+        let mut theirs = base.clone();
+        theirs.tasks.remove(2);
+        merge(Some(&base), &mut ours, &theirs);
+        assert_eq!(ours.get(2).unwrap().description, "改过的");
+    }
+
+    #[test]
+    fn unchanged_tasks_follow_deletions() {
+        let base = base();
+        let mut ours = base.clone();
+        edit(&mut ours, 1, |task| task.description = "改过的".to_string());
+        let mut theirs = base.clone();
+        theirs.tasks.remove(2);
+
+        merge(Some(&base), &mut ours, &theirs);
+
+        assert!(ours.get(2).is_none());
+        assert_eq!(ours.tasks.len(), 1);
+    }
+
+    #[test]
+    fn new_tasks_with_taken_ids_are_renumbered() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.add_task("我们的新任务".to_string(), "high").unwrap();
+        let mut child = Task::new(0, "子任务".to_string(), Priority::Low);
+        child.parent = Some(3);
+        child.depends = vec![3, 1];
+        ours.insert_task(child).unwrap();
+        let mut theirs = base.clone();
+        theirs.add_task("他们的新任务".to_string(), "low").unwrap();
+
+        merge(Some(&base), &mut ours, &theirs);
+
+        assert_eq!(ours.get(3).unwrap().description, "他们的新任务");
+        // 只有被占用的编号才重新分配
+        assert_eq!(ours.get(5).unwrap().description, "我们的新任务");
+        let child = ours.get(4).unwrap();
+        assert_eq!(child.description, "子任务");
+        assert_eq!(child.parent, Some(5));
+        assert_eq!(child.depends, vec![5, 1]);
+        assert_eq!(ours.next_id, 6);
+    }
+
+    #[test]
+    fn references_to_deleted_tasks_are_dropped() {
+        let base = base();
+        let mut ours = base.clone();
+        edit(&mut ours, 1, |task| {
+            task.depends = vec![2];
+            task.parent = Some(2);
+        });
+        let mut theirs = base.clone();
+        theirs.tasks.remove(2);
+
+        merge(Some(&base), &mut ours, &theirs);
+
+        let task = ours.get(1).unwrap();
+        assert!(task.depends.is_empty());
+        assert_eq!(task.parent, None);
+    }
+}
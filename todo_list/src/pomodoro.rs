@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::date;
+use crate::error::TodoError;
+use crate::storage::Store;
+use crate::task::Status;
+use crate::todo::TodoList;
+
+// 统计中按日期显示的天数
+const STATS_DAYS: usize = 7;
+
+// 番茄钟设置，时间单位为分钟
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pomodoro {
+    pub work: f64,
+    pub short_break: f64,
+    pub long_break: f64,
+    // 每完成几个番茄休息一次长休息
+    pub long_break_every: usize,
+    // 一次专注默认进行几个番茄
+    pub rounds: usize,
+}
+
+impl Default for Pomodoro {
+    fn default() -> Self {
+        Pomodoro {
+            work: 25.0,
+            short_break: 5.0,
+            long_break: 15.0,
+            long_break_every: 4,
+            rounds: 4,
+        }
+    }
+}
+
+// 专注过程中的一个阶段
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    // 第几个番茄
+    Work(usize),
+    ShortBreak,
+    LongBreak,
+}
+
+impl Phase {
+    pub fn minutes(self, settings: &Pomodoro) -> f64 {
+        match self {
+            Phase::Work(_) => settings.work,
+            Phase::ShortBreak => settings.short_break,
+            Phase::LongBreak => settings.long_break,
+        }
+    }
+}
+
+// 按设置排出 rounds 个番茄的顺序，最后一个番茄后不再休息
+pub fn schedule(settings: &Pomodoro, rounds: usize) -> Vec<Phase> {
+    let mut phases = Vec::new();
+    for round in 1..=rounds {
+        phases.push(Phase::Work(round));
+        if round == rounds {
+            break;
+        }
+        if settings.long_break_every > 0 && round % settings.long_break_every == 0 {
+            phases.push(Phase::LongBreak);
+        } else {
+            phases.push(Phase::ShortBreak);
+        }
+    }
+    phases
+}
+
+// 剩余时间显示为 分:秒
+fn clock(total: Duration, elapsed: Duration) -> String {
+    let left = total.saturating_sub(elapsed).as_secs();
+    format!("{:02}:{:02}", left / 60, left % 60)
+}
+
+// 在同一行显示倒计时，结束时响铃
+fn countdown(label: &str, minutes: f64) {
+    let total = Duration::from_secs((minutes * 60.0).round().max(0.0) as u64);
+    let start = Instant::now();
+    let mut stdout = std::io::stdout();
+    loop {
+        let elapsed = start.elapsed();
+        let _ = write!(stdout, "\r{}  {} ", label, clock(total, elapsed));
+        let _ = stdout.flush();
+        if elapsed >= total {
+            break;
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+    let _ = writeln!(stdout, "\x07");
+}
+
+// 番茄统计结果
+#[derive(Debug, Default, PartialEq)]
+pub struct PomodoroStats {
+    pub total: usize,
+    // (任务 id, 番茄数)，多的在前
+    pub by_task: Vec<(usize, usize)>,
+    // 最近几天的 (日期, 番茄数)，新的在前
+    pub by_day: Vec<(NaiveDate, usize)>,
+}
+
+impl TodoList {
+    // 没有指定任务时，选择紧急度最高且没有被阻塞的未结束任务
+    pub fn pick_focus_task(&self) -> Result<usize, TodoError> {
+        let now = date::now();
+        self.tasks
+            .iter()
+            .filter(|task| !task.status.is_closed() && !self.is_blocked(task))
+            .max_by(|a, b| self.urgency(a, now).total_cmp(&self.urgency(b, now)))
+            .map(|task| task.id)
+            .ok_or(TodoError::NoTask)
+    }
+
+    // 记录一个完成的番茄
+    pub fn log_pomodoro(&mut self, id: usize) -> Result<usize, TodoError> {
+        self.log_pomodoro_at(id, date::now())
+    }
+
+    pub fn log_pomodoro_at(&mut self, id: usize, time: NaiveDateTime) -> Result<usize, TodoError> {
+        self.tasks
+            .update(id, |task| {
+                task.pomodoros.push(time);
+                task.pomodoros.len()
+            })
+            .ok_or(TodoError::InvalidIndex)
+    }
+
+    // 番茄统计：按任务和按日期
+    pub fn pomodoro_stats(&self) -> PomodoroStats {
+        let mut by_task: Vec<_> = self
+            .tasks
+            .iter()
+            .filter(|t| !t.pomodoros.is_empty())
+            .map(|t| (t.id, t.pomodoros.len()))
+            .collect();
+        by_task.sort_by_key(|&(id, count)| (std::cmp::Reverse(count), id));
+        let mut days: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        for time in self.tasks.iter().flat_map(|t| &t.pomodoros) {
+            *days.entry(time.date()).or_default() += 1;
+        }
+        PomodoroStats {
+            total: by_task.iter().map(|&(_, count)| count).sum(),
+            by_task,
+            by_day: days.into_iter().rev().take(STATS_DAYS).collect(),
+        }
+    }
+
+    pub fn show_pomodoro_stats(&self) {
+        let stats = self.pomodoro_stats();
+        if stats.total == 0 {
+            return;
+        }
+        let cfg = &self.config;
+        println!("\n{}{}", cfg.icon("🍅 "), cfg.tr("番茄钟:", "Pomodoros:"));
+        println!("{}: {}", cfg.tr("总数", "Total"), stats.total);
+
+        println!("\n{}", cfg.tr("按任务:", "By task:"));
+        for (id, count) in stats.by_task {
+            let description = self.get(id).map(|t| t.description.as_str()).unwrap_or_default();
+            println!("#{} {}: {}", id, description, count);
+        }
+
+        println!("\n{}", cfg.tr("按日期（最近 7 天）:", "By day (last 7 days):"));
+        for (day, count) in stats.by_day {
+            println!("{}: {}", day.format(&cfg.date_format), count);
+        }
+    }
+}
+
+// 专注模式：对一个任务进行若干个番茄，每完成一个记录到任务上并保存
+pub fn focus(
+    todo: &mut TodoList,
+    store: &mut Store,
+    id: Option<usize>,
+    rounds: Option<usize>,
+) -> Result<(), TodoError> {
+    let id = match id {
+        Some(id) => id,
+        None => todo.pick_focus_task()?,
+    };
+    let task = todo.get(id).ok_or(TodoError::InvalidIndex)?;
+    if task.status.is_closed() {
+        return Err(TodoError::InvalidTransition(task.status, Status::InProgress));
+    }
+    if task.status == Status::Todo {
+        todo.set_status(id, Status::InProgress)?;
+        store.save(todo)?;
+    }
+
+    let settings = todo.config.pomodoro.clone();
+    let rounds = rounds.unwrap_or(settings.rounds).max(1);
+    let description = todo.get(id).map(|t| t.description.clone()).unwrap_or_default();
+    println!(
+        "🎯 开始专注 #{} {}：{} 个番茄，每个 {} 分钟（Ctrl-C 退出，未完成的番茄不记录）",
+        id, description, rounds, settings.work
+    );
+    for phase in schedule(&settings, rounds) {
+        let minutes = phase.minutes(&settings);
+        match phase {
+            Phase::Work(round) => {
+                countdown(&format!("🍅 {}/{} #{} {}", round, rounds, id, description), minutes);
+                let count = todo.log_pomodoro(id)?;
+                store.save(todo)?;
+                println!("✅ 完成一个番茄，这个任务累计 {} 个", count);
+            }
+            Phase::LongBreak => countdown("☕ 长休息", minutes),
+            Phase::ShortBreak => countdown("☕ 休息", minutes),
+        }
+    }
+    println!("🎉 专注结束！");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::parse_due;
+
+    #[test]
+    fn schedule_alternates_breaks_and_ends_on_work() {
+        let settings = Pomodoro { long_break_every: 2, ..Default::default() };
+        assert_eq!(
+            schedule(&settings, 5),
+            vec![
+                Phase::Work(1),
+                Phase::ShortBreak,
+                Phase::Work(2),
+                Phase::LongBreak,
+                Phase::Work(3),
+                Phase::ShortBreak,
+                Phase::Work(4),
+                Phase::LongBreak,
+                Phase::Work(5),
+            ]
+        );
+        assert_eq!(schedule(&settings, 1), vec![Phase::Work(1)]);
+        assert!(schedule(&settings, 0).is_empty());
+
+        // long_break_every 为 0 时只有短休息
+        let settings = Pomodoro { long_break_every: 0, ..Default::default() };
+        assert!(!schedule(&settings, 6).contains(&Phase::LongBreak));
+        assert_eq!(Phase::LongBreak.minutes(&settings), 15.0);
+        assert_eq!(Phase::Work(3).minutes(&settings), 25.0);
+    }
+
+    #[test]
+    fn clock_counts_down_and_stops_at_zero() {
+        let total = Duration::from_secs(25 * 60);
+        assert_eq!(clock(total, Duration::ZERO), "25:00");
+        assert_eq!(clock(total, Duration::from_millis(61_500)), "23:58");
+        assert_eq!(clock(total, total * 2), "00:00");
+    }
+
+    #[test]
+    fn stats_total_per_task_and_per_day() {
+        let mut todo = TodoList::new();
+        for description in ["a", "b", "c"] {
+            todo.add_task(description.to_string(), "medium").unwrap();
+        }
+        assert_eq!(todo.pomodoro_stats(), PomodoroStats::default());
+
+        let monday = parse_due("2024-03-04").unwrap();
+        let tuesday = parse_due("2024-03-05").unwrap();
+        assert_eq!(todo.log_pomodoro_at(1, monday).unwrap(), 1);
+        assert_eq!(todo.log_pomodoro_at(3, monday).unwrap(), 1);
+        assert_eq!(todo.log_pomodoro_at(3, tuesday).unwrap(), 2);
+        assert_eq!(todo.log_pomodoro_at(2, tuesday).unwrap(), 1);
+        assert!(todo.log_pomodoro_at(9, monday).is_err());
+
+        let stats = todo.pomodoro_stats();
+        assert_eq!(stats.total, 4);
+        assert_eq!(stats.by_task, vec![(3, 2), (1, 1), (2, 1)]);
+        assert_eq!(stats.by_day, vec![(tuesday.date(), 2), (monday.date(), 2)]);
+    }
+
+    #[test]
+    fn stats_keep_only_the_latest_days() {
+        let mut todo = TodoList::new();
+        todo.add_task("a".to_string(), "medium").unwrap();
+        let start = parse_due("2024-03-01").unwrap();
+        for day in 0..10 {
+            todo.log_pomodoro_at(1, start + chrono::Duration::days(day)).unwrap();
+        }
+        let stats = todo.pomodoro_stats();
+        assert_eq!(stats.total, 10);
+        assert_eq!(stats.by_day.len(), STATS_DAYS);
+        assert_eq!(stats.by_day[0].0, (start + chrono::Duration::days(9)).date());
+    }
+}
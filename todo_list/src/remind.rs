@@ -0,0 +1,290 @@
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration as StdDuration;
+
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::date;
+use crate::error::TodoError;
+use crate::estimate;
+use crate::hooks::UserCommand;
+use crate::storage::Store;
+use crate::task::Task;
+use crate::todo::TodoList;
+
+// 提醒设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Remind {
+    // 截止前多久提醒，例如 1d、2h、15m，0 表示到截止时间时提醒
+    pub before: Vec<String>,
+    // 检查间隔，单位为秒
+    pub interval: u64,
+    // 是否在终端输出
+    pub terminal: bool,
+    // 追加写入提醒的日志文件
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<PathBuf>,
+    // 提醒时执行的命令，任务以 JSON 写入标准输入
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+impl Default for Remind {
+    fn default() -> Self {
+        Remind {
+            before: vec!["1d".to_string(), "1h".to_string(), "0".to_string()],
+            interval: 60,
+            terminal: true,
+            log_file: None,
+            command: None,
+        }
+    }
+}
+
+// 提醒最多提前这么多天
+const MAX_OFFSET_DAYS: i64 = 3650;
+
+// 解析提醒提前量：30、30m、2h、1d，最多提前 MAX_OFFSET_DAYS 天
+fn parse_offset(s: &str) -> Result<Duration, TodoError> {
+    let s = s.trim().to_lowercase();
+    let invalid = || TodoError::Config(format!("无效的提醒时间 {}，可用 30m、2h、1d 这样的格式", s));
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: i64 = number.parse().map_err(|_| invalid())?;
+    let offset = match unit {
+        "" | "m" => Duration::try_minutes(number),
+        "h" => Duration::try_hours(number),
+        "d" => Duration::try_days(number),
+        _ => return Err(invalid()),
+    };
+    offset.filter(|offset| offset.num_days() <= MAX_OFFSET_DAYS).ok_or_else(|| {
+        TodoError::Config(format!("提醒时间 {} 太长，最多提前 {} 天", s, MAX_OFFSET_DAYS))
+    })
+}
+
+// 一条要发出的提醒
+struct Reminder<'a> {
+    task: &'a Task,
+    message: String,
+}
+
+// 已发出的提醒记在数据文件旁边，重启后不会重复提醒。
+// 每条记录包含任务编号、截止时间和提前量，修改截止时间后会重新提醒
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Sent {
+    sent: BTreeSet<String>,
+}
+
+fn key(task: &Task, due: NaiveDateTime, offset: Duration) -> String {
+    format!("{}@{}#{}", task.id, due.format("%Y-%m-%dT%H:%M"), offset.num_minutes())
+}
+
+impl Sent {
+    fn load(path: &Path) -> Result<Sent, TodoError> {
+        if !path.exists() {
+            return Ok(Sent::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    fn save(&self, path: &Path) -> Result<(), TodoError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    // 去掉已删除、已结束或改过截止时间的任务的记录
+    fn prune(&mut self, todo: &TodoList) {
+        let live: BTreeSet<String> = todo
+            .tasks
+            .iter()
+            .filter(|task| !task.status.is_closed())
+            .filter_map(|task| task.due.map(|due| format!("{}@{}#", task.id, due.format("%Y-%m-%dT%H:%M"))))
+            .collect();
+        self.sent
+            .retain(|key| key.find('#').is_some_and(|i| live.contains(&key[..=i])));
+    }
+}
+
+// 找出到了提醒时间的任务。同一任务错过了多个提醒时只发最近的一个，其余的一并记为已发出
+fn due_reminders<'a>(
+    todo: &'a TodoList,
+    offsets: &[Duration],
+    sent: &mut Sent,
+    now: NaiveDateTime,
+) -> Vec<Reminder<'a>> {
+    let mut reminders = Vec::new();
+    for task in todo.tasks.iter().filter(|task| !task.status.is_closed()) {
+        let Some(due) = task.due else {
+            continue;
+        };
+        let passed: Vec<Duration> = offsets
+            .iter()
+            .copied()
+            .filter(|&offset| due.checked_sub_signed(offset).is_none_or(|at| at <= now))
+            .filter(|&offset| !sent.sent.contains(&key(task, due, offset)))
+            .collect();
+        if passed.is_empty() {
+            continue;
+        }
+        for &offset in &passed {
+            sent.sent.insert(key(task, due, offset));
+        }
+        let left = (due - now).num_minutes();
+        let message = if left > 0 {
+            format!(
+                "⏰ 任务 #{} {} 还有 {} 截止（{}）",
+                task.id,
+                task.description,
+                estimate::format_minutes(left as f64),
+                todo.config.format_due(due)
+            )
+        } else if left == 0 {
+            format!("⏰ 任务 #{} {} 已到截止时间（{}）", task.id, task.description, todo.config.format_due(due))
+        } else {
+            format!("⏰ 任务 #{} {} 已逾期（{}）", task.id, task.description, todo.config.format_due(due))
+        };
+        reminders.push(Reminder { task, message });
+    }
+    reminders
+}
+
+fn run_command(command: &str, reminder: &Reminder) -> Result<(), TodoError> {
+    let Some(command) = UserCommand::parse(command) else {
+        return Ok(());
+    };
+    let task = reminder.task;
+    let envs = [
+        ("TODO_REMIND_ID", task.id.to_string()),
+        ("TODO_REMIND_DESCRIPTION", task.description.clone()),
+        ("TODO_REMIND_DUE", task.due.map(|due| due.to_string()).unwrap_or_default()),
+        ("TODO_REMIND_MESSAGE", reminder.message.clone()),
+    ];
+    let program = command.program.display();
+    let output = command
+        .run(&envs, task, false)
+        .map_err(|e| TodoError::Io(format!("{}: {}", program, e)))?;
+    if !output.status.success() {
+        return Err(TodoError::Io(format!("{} 退出码 {}", program, output.status)));
+    }
+    Ok(())
+}
+
+// 按设置把提醒输出到终端、日志文件或命令
+fn deliver(settings: &Remind, reminder: &Reminder) -> Result<(), TodoError> {
+    if settings.terminal {
+        println!("{}\x07", reminder.message);
+    }
+    if let Some(log_file) = &settings.log_file {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(config::expand_home(log_file))?;
+        writeln!(file, "[{}] {}", date::now().format("%Y-%m-%d %H:%M:%S"), reminder.message)?;
+    }
+    if let Some(command) = &settings.command {
+        run_command(command, reminder)?;
+    }
+    Ok(())
+}
+
+// 检查一次：重新读取数据文件，发出到时间的提醒并记录下来
+fn check(todo: &mut TodoList, store: &mut Store, offsets: &[Duration], path: &Path) -> Result<(), TodoError> {
+    if let Some(mut latest) = store.load()? {
+        latest.config = todo.config.clone();
+        *todo = latest;
+    }
+    let mut sent = Sent::load(path)?;
+    let before = sent.sent.clone();
+    sent.prune(todo);
+    let settings = todo.config.remind.clone();
+    for reminder in due_reminders(todo, offsets, &mut sent, date::now()) {
+        if let Err(e) = deliver(&settings, &reminder) {
+            println!("🙅‍♂️{}", e);
+        }
+    }
+    if sent.sent != before {
+        sent.save(path)?;
+    }
+    Ok(())
+}
+
+// 提醒模式：定期检查截止时间，once 时只检查一次就退出（适合放在 cron 中）
+pub fn run(todo: &mut TodoList, store: &mut Store, once: bool) -> Result<(), TodoError> {
+    let settings = todo.config.remind.clone();
+    let offsets = settings.before.iter().map(|s| parse_offset(s)).collect::<Result<Vec<_>, _>>()?;
+    let path = store.reminders_path();
+    if once {
+        return check(todo, store, &offsets, &path);
+    }
+    println!(
+        "🔔 提醒已启动：截止前 {} 提醒，每 {} 秒检查一次（Ctrl-C 退出）",
+        settings.before.join("、"),
+        settings.interval
+    );
+    loop {
+        if let Err(e) = check(todo, store, &offsets, &path) {
+            println!("🙅‍♂️{}", e);
+        }
+        thread::sleep(StdDuration::from_secs(settings.interval.max(1)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 5, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn parse_offset_accepts_minutes_hours_and_days() {
+        assert_eq!(parse_offset("30").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_offset("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_offset(" 2H ").unwrap(), Duration::hours(2));
+        assert_eq!(parse_offset("1d").unwrap(), Duration::days(1));
+        assert_eq!(parse_offset("3650d").unwrap(), Duration::days(MAX_OFFSET_DAYS));
+    }
+
+    #[test]
+    fn parse_offset_rejects_bad_or_huge_values() {
+        for s in ["", "m", "-5m", "1w", "2h30m", "3651d", "99999999999999d", "99999999999999999999"] {
+            assert!(parse_offset(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn due_reminders_fire_once_per_offset() {
+        let mut todo = TodoList::new();
+        todo.add_task("交报告".to_string(), "medium").unwrap();
+        todo.add_task("没有截止时间".to_string(), "medium").unwrap();
+        todo.tasks.update(1, |task| task.due = Some(at(10, 12)));
+        let offsets = [Duration::hours(1), Duration::days(1)];
+        let mut sent = Sent::default();
+
+        assert!(due_reminders(&todo, &offsets, &mut sent, at(9, 11)).is_empty());
+        let reminders = due_reminders(&todo, &offsets, &mut sent, at(9, 12));
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].task.id, 1);
+        assert!(due_reminders(&todo, &offsets, &mut sent, at(9, 13)).is_empty());
+        assert_eq!(due_reminders(&todo, &offsets, &mut sent, at(10, 11)).len(), 1);
+        assert!(due_reminders(&todo, &offsets, &mut sent, at(10, 12)).is_empty());
+    }
+
+    #[test]
+    fn due_reminders_handle_offsets_before_the_earliest_date() {
+        let mut todo = TodoList::new();
+        todo.add_task("很早以前".to_string(), "medium").unwrap();
+        todo.tasks.update(1, |task| task.due = Some(NaiveDateTime::MIN));
+        let mut sent = Sent::default();
+        let reminders = due_reminders(&todo, &[Duration::days(MAX_OFFSET_DAYS)], &mut sent, at(1, 0));
+        assert_eq!(reminders.len(), 1);
+    }
+}
@@ -0,0 +1,340 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::date;
+use crate::error::TodoError;
+use crate::task::{Priority, Status, Task};
+use crate::todo::TodoList;
+
+// 报告格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Markdown,
+    Html,
+}
+
+impl FromStr for Format {
+    type Err = TodoError;
+
+    fn from_str(s: &str) -> Result<Format, TodoError> {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(Format::Markdown),
+            "html" | "htm" => Ok(Format::Html),
+            _ => Err(TodoError::Report(format!("不支持的格式 {}，可用 markdown 或 html", s))),
+        }
+    }
+}
+
+impl Format {
+    // 没有指定格式时按输出文件的扩展名判断
+    pub fn detect(format: Option<&str>, output: Option<&Path>) -> Result<Format, TodoError> {
+        if let Some(format) = format {
+            return Format::from_str(format);
+        }
+        let html = output
+            .and_then(|path| path.extension())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"));
+        Ok(if html { Format::Html } else { Format::Markdown })
+    }
+}
+
+// 某一周的周一到周日，weeks_ago 为 0 表示本周；超出日期范围时报错
+pub fn week(today: NaiveDate, weeks_ago: u32) -> Result<(NaiveDate, NaiveDate), TodoError> {
+    let back = today.weekday().num_days_from_monday() as i64 + 7 * weeks_ago as i64;
+    let monday = Duration::try_days(back).and_then(|back| today.checked_sub_signed(back));
+    let sunday = monday.and_then(|monday| monday.checked_add_signed(Duration::days(6)));
+    match (monday, sunday) {
+        (Some(monday), Some(sunday)) => Ok((monday, sunday)),
+        _ => Err(TodoError::Report(format!("{} 周前超出了日期范围", weeks_ago))),
+    }
+}
+
+// 简单的文档，同时支持 Markdown 和 HTML
+struct Doc {
+    format: Format,
+    body: String,
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+impl Doc {
+    fn heading(&mut self, level: usize, text: &str) {
+        match self.format {
+            Format::Markdown => self.body.push_str(&format!("{} {}\n\n", "#".repeat(level), text)),
+            Format::Html => self.body.push_str(&format!("<h{0}>{1}</h{0}>\n", level, escape(text))),
+        }
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        match self.format {
+            Format::Markdown => self.body.push_str(&format!("{}\n\n", text)),
+            Format::Html => self.body.push_str(&format!("<p>{}</p>\n", escape(text))),
+        }
+    }
+
+    fn list(&mut self, items: &[String]) {
+        match self.format {
+            Format::Markdown => {
+                for item in items {
+                    self.body.push_str(&format!("- {}\n", item));
+                }
+                self.body.push('\n');
+            }
+            Format::Html => {
+                self.body.push_str("<ul>\n");
+                for item in items {
+                    self.body.push_str(&format!("  <li>{}</li>\n", escape(item)));
+                }
+                self.body.push_str("</ul>\n");
+            }
+        }
+    }
+
+    fn finish(self, title: &str) -> String {
+        match self.format {
+            Format::Markdown => self.body,
+            Format::Html => format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+                 <style>body {{ font-family: sans-serif; max-width: 50em; margin: 2em auto; }} \
+                 h4 {{ color: #666; }}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+                escape(title),
+                self.body
+            ),
+        }
+    }
+}
+
+// 报告中的一部分，例如本周完成的任务
+struct Section<'a> {
+    title: &'a str,
+    tasks: Vec<&'a Task>,
+}
+
+// 完成时间：旧数据没有记录时用最后修改时间代替
+fn completed_at(task: &Task) -> Option<NaiveDateTime> {
+    match task.status {
+        Status::Done => task.completed.or(task.modified),
+        _ => None,
+    }
+}
+
+impl TodoList {
+    // 一个任务在报告中的一行
+    fn report_line(&self, task: &Task, now: NaiveDateTime) -> String {
+        let cfg = &self.config;
+        let mut details = Vec::new();
+        if let Some(completed) = completed_at(task) {
+            details.push(format!("{} {}", cfg.tr("完成于", "done"), cfg.format_datetime(completed)));
+        } else if let Some(due) = task.due {
+            details.push(format!("{} {}", cfg.tr("截止", "due"), cfg.format_due(due)));
+            let days = (now - due).num_days();
+            if due < now && !task.status.is_closed() && days > 0 {
+                details.push(format!("{} {} {}", cfg.tr("逾期", "overdue"), days, cfg.tr("天", "days")));
+            }
+        }
+        if !task.status.is_closed() && task.status != Status::Todo {
+            details.push(cfg.status_name(task.status).to_string());
+        }
+        if !task.tags.is_empty() {
+            let tags: Vec<String> = task.tags.iter().map(|t| format!("#{}", t)).collect();
+            details.push(tags.join(" "));
+        }
+        match details.is_empty() {
+            true => format!("#{} {}", task.id, task.description),
+            false => format!("#{} {}（{}）", task.id, task.description, details.join("，")),
+        }
+    }
+
+    // 报告的各个部分：完成、新增、逾期，以及延续（范围开始前创建、到范围结束时仍未结束）
+    fn report_sections(&self, from: NaiveDate, to: NaiveDate, now: NaiveDateTime) -> Vec<Section<'_>> {
+        let cfg = &self.config;
+        let in_range = |time: NaiveDateTime| time.date() >= from && time.date() <= to;
+        let end = now.min(to.succ_opt().map_or(NaiveDateTime::MAX, |next| next.and_time(NaiveTime::MIN)));
+        // 范围结束时还没完成的任务；取消的任务没有记录时间，不算在内
+        let open = |task: &&Task| {
+            task.created < end
+                && match task.status {
+                    Status::Cancelled => false,
+                    Status::Done => completed_at(task).is_some_and(|completed| completed >= end),
+                    _ => true,
+                }
+        };
+        vec![
+            Section {
+                title: cfg.tr("✅ 已完成", "✅ Completed"),
+                tasks: self.tasks.iter().filter(|t| completed_at(t).is_some_and(in_range)).collect(),
+            },
+            Section {
+                title: cfg.tr("🆕 新增", "🆕 Added"),
+                tasks: self.tasks.iter().filter(|t| in_range(t.created)).collect(),
+            },
+            Section {
+                title: cfg.tr("⏰ 已逾期", "⏰ Overdue"),
+                tasks: self.tasks.iter().filter(open).filter(|t| t.due.is_some_and(|due| due < end)).collect(),
+            },
+            Section {
+                title: cfg.tr("➡️ 延续", "➡️ Carried over"),
+                tasks: self.tasks.iter().filter(open).filter(|t| t.created.date() < from).collect(),
+            },
+        ]
+    }
+
+    // 生成 from 到 to（含）的报告，各部分按优先级和项目分组
+    pub fn report(&self, from: NaiveDate, to: NaiveDate, format: Format) -> String {
+        let cfg = &self.config;
+        let now = date::now();
+        let sections = self.report_sections(from, to, now);
+        let range = format!("{} ~ {}", from.format(&cfg.date_format), to.format(&cfg.date_format));
+        let title = format!("{} {}", cfg.tr("任务报告", "Task report"), range);
+
+        let mut doc = Doc { format, body: String::new() };
+        doc.heading(1, &title);
+        let summary: Vec<String> = sections
+            .iter()
+            .map(|section| format!("{} {}", section.title, section.tasks.len()))
+            .collect();
+        doc.paragraph(&summary.join(" · "));
+
+        for section in &sections {
+            doc.heading(2, &format!("{} ({})", section.title, section.tasks.len()));
+            if section.tasks.is_empty() {
+                doc.paragraph(cfg.tr("无", "None"));
+                continue;
+            }
+            for priority in [Priority::High, Priority::Medium, Priority::Low] {
+                let tasks: Vec<&Task> = section.tasks.iter().copied().filter(|t| t.priority == priority).collect();
+                if tasks.is_empty() {
+                    continue;
+                }
+                doc.heading(3, &cfg.priority_text(priority));
+                let mut projects: Vec<Option<&str>> = tasks.iter().map(|t| t.project.as_deref()).collect();
+                projects.sort();
+                projects.dedup();
+                for project in projects {
+                    let lines: Vec<String> = tasks
+                        .iter()
+                        .filter(|t| t.project.as_deref() == project)
+                        .map(|t| self.report_line(t, now))
+                        .collect();
+                    doc.heading(4, project.unwrap_or(cfg.tr("（无项目）", "(none)")));
+                    doc.list(&lines);
+                }
+            }
+        }
+        doc.finish(&title)
+    }
+
+    // 输出报告，没有指定文件时输出到标准输出
+    pub fn write_report(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        format: Format,
+        output: Option<&Path>,
+    ) -> Result<(), TodoError> {
+        if from > to {
+            return Err(TodoError::Report(format!("开始日期 {} 晚于结束日期 {}", from, to)));
+        }
+        let report = self.report(from, to, format);
+        match output.filter(|path| *path != Path::new("-")) {
+            Some(path) => {
+                fs::write(path, report)?;
+                println!("📝 已生成报告 {}", path.display());
+            }
+            None => print!("{}", report),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, d).unwrap()
+    }
+
+    fn at(d: u32) -> NaiveDateTime {
+        day(d).and_hms_opt(12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn week_runs_from_monday_to_sunday() {
+        // 2024-05-06 是周一，2024-05-12 是周日
+        assert_eq!(week(day(6), 0).unwrap(), (day(6), day(12)));
+        assert_eq!(week(day(12), 0).unwrap(), (day(6), day(12)));
+        assert_eq!(week(day(13), 0).unwrap(), (day(13), day(19)));
+        assert_eq!(week(day(12), 1).unwrap(), (NaiveDate::from_ymd_opt(2024, 4, 29).unwrap(), day(5)));
+        assert!(week(day(6), 99_999_999).is_err());
+        assert!(week(day(6), u32::MAX).is_err());
+        assert!(week(NaiveDate::MAX, 0).is_err());
+    }
+
+    // 第 6 日到第 12 日的报告中各部分的任务
+    fn sections(todo: &TodoList) -> Vec<Vec<&str>> {
+        todo.report_sections(day(6), day(12), at(20))
+            .iter()
+            .map(|section| section.tasks.iter().map(|task| task.description.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn report_sections_pick_the_right_tasks() {
+        let mut todo = TodoList::new();
+        let mut add = |description: &str, created: u32, status: Status, completed: Option<u32>, due: Option<u32>| {
+            todo.add_task(description.to_string(), "medium").unwrap();
+            let id = todo.next_id - 1;
+            todo.tasks.update(id, |task| {
+                task.created = at(created);
+                task.modified = Some(at(created));
+                task.status = status;
+                task.completed = completed.map(at);
+                task.due = due.map(at);
+            });
+        };
+        add("范围内完成", 1, Status::Done, Some(8), None);
+        add("范围内新增", 7, Status::Todo, None, Some(9));
+        add("一直没做", 1, Status::InProgress, None, None);
+        add("范围后才完成", 1, Status::Done, Some(15), Some(3));
+        add("已取消", 1, Status::Cancelled, None, Some(3));
+        add("范围后新增", 14, Status::Todo, None, Some(9));
+        add("没有完成时间", 1, Status::Done, None, None);
+        todo.tasks.update(7, |task| task.modified = Some(at(10)));
+
+        assert_eq!(
+            sections(&todo),
+            vec![
+                vec!["范围内完成", "没有完成时间"],
+                vec!["范围内新增"],
+                vec!["范围内新增", "范围后才完成"],
+                vec!["一直没做", "范围后才完成"],
+            ]
+        );
+    }
+
+    #[test]
+    fn html_report_escapes_text() {
+        let mut todo = TodoList::new();
+        todo.add_task("修复 <script> & 引号".to_string(), "high").unwrap();
+        let today = date::today();
+        let html = todo.report(today, today, Format::Html);
+        assert!(html.contains("修复 &lt;script&gt; &amp; 引号"));
+        assert!(!html.contains("<script>"));
+        let markdown = todo.report(today, today, Format::Markdown);
+        assert!(markdown.contains("- #1 修复 <script> & 引号"));
+    }
+
+    #[test]
+    fn format_is_detected_from_the_extension() {
+        assert_eq!(Format::detect(None, Some(Path::new("a.HTML"))).unwrap(), Format::Html);
+        assert_eq!(Format::detect(None, Some(Path::new("a.md"))).unwrap(), Format::Markdown);
+        assert_eq!(Format::detect(Some("html"), None).unwrap(), Format::Html);
+        assert!(Format::detect(Some("pdf"), None).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::date;
+use crate::error::TodoError;
+use crate::task::Task;
+use crate::todo::TodoList;
+
+// 可排序的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    Id,
+    Priority,
+    Status,
+    Due,
+    Created,
+    Description,
+    Urgency,
+}
+
+impl SortField {
+    fn name(self) -> &'static str {
+        match self {
+            SortField::Id => "id",
+            SortField::Priority => "priority",
+            SortField::Status => "status",
+            SortField::Due => "due",
+            SortField::Created => "created",
+            SortField::Description => "description",
+            SortField::Urgency => "urgency",
+        }
+    }
+
+    fn from_str(s: &str) -> Result<SortField, TodoError> {
+        match s.to_lowercase().as_str() {
+            "id" | "编号" => Ok(SortField::Id),
+            "priority" | "prio" | "优先级" => Ok(SortField::Priority),
+            "status" | "状态" => Ok(SortField::Status),
+            "due" | "截止" => Ok(SortField::Due),
+            "created" | "entry" | "创建" => Ok(SortField::Created),
+            "description" | "desc" | "描述" => Ok(SortField::Description),
+            "urgency" | "紧急度" => Ok(SortField::Urgency),
+            _ => Err(TodoError::InvalidSort(s.to_string())),
+        }
+    }
+}
+
+// 单个排序键，descending 为 true 时倒序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+// 排序规则，例如 priority,-due,created
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SortSpec {
+    pub keys: Vec<SortKey>,
+}
+
+impl SortSpec {
+    pub fn parse(s: &str) -> Result<SortSpec, TodoError> {
+        let mut keys = Vec::new();
+        for part in s.split([',', '，']) {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            let (descending, name) = match part.strip_prefix('-') {
+                Some(name) => (true, name),
+                None => (false, part.strip_prefix('+').unwrap_or(part)),
+            };
+            keys.push(SortKey {
+                field: SortField::from_str(name)?,
+                descending,
+            });
+        }
+        Ok(SortSpec { keys })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    // 按排序规则排序，相同时按编号。规则里没有 status 时，先把未结束的任务排在已结束的前面
+    pub fn sort(&self, todo: &TodoList, tasks: &mut [&Task]) {
+        if self.is_empty() {
+            return;
+        }
+
+        let urgency: HashMap<usize, f64> = if self.uses(SortField::Urgency) {
+            let now = date::now();
+            tasks
+                .iter()
+                .map(|t| (t.id, todo.urgency(t, now)))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        let group_by_status = !self.uses(SortField::Status);
+
+        tasks.sort_by(|a, b| {
+            let group = if group_by_status {
+                a.status.is_closed().cmp(&b.status.is_closed())
+            } else {
+                Ordering::Equal
+            };
+            self.keys
+                .iter()
+                .fold(group, |ord, key| {
+                    ord.then_with(|| {
+                        let ord = compare(key.field, a, b, &urgency);
+                        let missing_due =
+                            key.field == SortField::Due && (a.due.is_none() || b.due.is_none());
+                        if key.descending && !missing_due {
+                            ord.reverse()
+                        } else {
+                            ord
+                        }
+                    })
+                })
+                .then_with(|| a.id.cmp(&b.id))
+        });
+    }
+
+    fn uses(&self, field: SortField) -> bool {
+        self.keys.iter().any(|k| k.field == field)
+    }
+}
+
+impl fmt::Display for SortSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let keys: Vec<String> = self
+            .keys
+            .iter()
+            .map(|k| format!("{}{}", if k.descending { "-" } else { "" }, k.field.name()))
+            .collect();
+        write!(f, "{}", keys.join(","))
+    }
+}
+
+// 没有截止时间的任务总是排在最后，不受正序倒序影响
+fn compare(field: SortField, a: &Task, b: &Task, urgency: &HashMap<usize, f64>) -> Ordering {
+    match field {
+        SortField::Id => a.id.cmp(&b.id),
+        SortField::Priority => a.priority.cmp(&b.priority),
+        SortField::Status => a.status.cmp(&b.status),
+        SortField::Due => match (a.due, b.due) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+        SortField::Created => a.created.cmp(&b.created),
+        SortField::Description => a.description.to_lowercase().cmp(&b.description.to_lowercase()),
+        SortField::Urgency => {
+            let score = |t: &Task| urgency.get(&t.id).copied().unwrap_or(0.0);
+            // 紧急度默认从高到低
+            score(b).total_cmp(&score(a))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Priority, Status};
+
+    fn keys(s: &str) -> Vec<(SortField, bool)> {
+        SortSpec::parse(s).unwrap().keys.iter().map(|k| (k.field, k.descending)).collect()
+    }
+
+    #[test]
+    fn parse_reads_fields_and_directions() {
+        assert_eq!(
+            keys("priority, -due,+created，描述"),
+            vec![
+                (SortField::Priority, false),
+                (SortField::Due, true),
+                (SortField::Created, false),
+                (SortField::Description, false),
+            ]
+        );
+        assert_eq!(keys("-URGENCY"), vec![(SortField::Urgency, true)]);
+        assert!(SortSpec::parse("").unwrap().is_empty());
+        assert!(SortSpec::parse(" , ").unwrap().is_empty());
+        for s in ["colour", "priority,-", "--due", "due desc"] {
+            assert!(matches!(SortSpec::parse(s), Err(TodoError::InvalidSort(_))), "{}", s);
+        }
+        assert_eq!(SortSpec::parse("prio,-截止").unwrap().to_string(), "priority,-due");
+    }
+
+    // 编号 1..=5 的任务：(优先级, 状态, 截止日)
+    fn todo() -> TodoList {
+        let mut todo = TodoList::new();
+        let tasks = [
+            (Priority::Low, Status::Todo, Some(3)),
+            (Priority::High, Status::Done, Some(1)),
+            (Priority::Medium, Status::Todo, None),
+            (Priority::High, Status::InProgress, Some(2)),
+            (Priority::Medium, Status::Todo, Some(2)),
+        ];
+        for (i, (priority, status, due)) in tasks.into_iter().enumerate() {
+            todo.add_task(format!("任务{}", i + 1), "medium").unwrap();
+            todo.tasks.update(i + 1, |task| {
+                task.priority = priority;
+                task.status = status;
+                task.due = due.map(|d| date::parse_due(&format!("2024-05-0{}", d)).unwrap());
+            });
+        }
+        todo
+    }
+
+    fn sorted(todo: &TodoList, spec: &str) -> Vec<usize> {
+        // 故意打乱输入顺序，结果不应依赖输入顺序
+        let mut tasks: Vec<&Task> = todo.tasks.iter().rev().collect();
+        SortSpec::parse(spec).unwrap().sort(todo, &mut tasks);
+        tasks.iter().map(|task| task.id).collect()
+    }
+
+    #[test]
+    fn closed_tasks_go_last_unless_sorting_by_status() {
+        let todo = todo();
+        assert_eq!(sorted(&todo, "priority"), vec![4, 3, 5, 1, 2]);
+        assert_eq!(sorted(&todo, "status"), vec![1, 3, 5, 4, 2]);
+        assert_eq!(sorted(&todo, "-status"), vec![2, 4, 1, 3, 5]);
+    }
+
+    #[test]
+    fn tasks_without_due_date_sort_last_in_both_directions() {
+        let todo = todo();
+        assert_eq!(sorted(&todo, "due"), vec![4, 5, 1, 3, 2]);
+        assert_eq!(sorted(&todo, "-due"), vec![1, 4, 5, 3, 2]);
+    }
+
+    #[test]
+    fn ties_are_broken_by_id() {
+        let todo = todo();
+        assert_eq!(sorted(&todo, "description"), vec![1, 3, 4, 5, 2]);
+        assert_eq!(sorted(&todo, "-priority"), vec![1, 3, 5, 4, 2]);
+        assert_eq!(sorted(&todo, "priority,-id"), vec![4, 5, 3, 1, 2]);
+        // 没有规则时保持原来的顺序
+        assert_eq!(sorted(&todo, ""), vec![5, 4, 3, 2, 1]);
+    }
+}
@@ -0,0 +1,510 @@
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::crypto::{self, Sealer};
+use crate::error::TodoError;
+use crate::history;
+use crate::journal::{self, Entry};
+use crate::merge;
+use crate::sync;
+use crate::todo::TodoList;
+
+// 在路径后面加上后缀，例如 todo.json.lock
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+// 数据文件的读写
+pub struct Store {
+    path: PathBuf,
+    git: bool,
+    encrypt: bool,
+    // 日志模式：数据文件是快照，之后的修改追加到日志文件
+    journal: Option<PathBuf>,
+    snapshot_every: usize,
+    // 日志中有效的条数和字节数，最后一行被截断时之后的追加会先把它去掉
+    journal_entries: usize,
+    journal_len: u64,
+    // 已经输入过口令时保存派生出的密钥
+    sealer: Option<Sealer>,
+    // 上次读取或保存的内容，保存时用来和其他进程的修改合并
+    last: Option<TodoList>,
+}
+
+impl Store {
+    pub fn new(config: &Config) -> Self {
+        Store {
+            path: config.data_file(),
+            git: config.storage.git,
+            encrypt: config.storage.encrypt,
+            journal: config
+                .storage
+                .journal
+                .then(|| with_suffix(&config.data_file(), ".journal")),
+            snapshot_every: config.storage.snapshot_every,
+            journal_entries: 0,
+            journal_len: 0,
+            sealer: None,
+            last: None,
+        }
+    }
+
+    // 读取任务列表，文件不存在时返回 None
+    pub fn load(&mut self) -> Result<Option<TodoList>, TodoError> {
+        // 日志模式要同时读快照和日志，加锁避免读到压缩到一半的状态
+        let _lock = match self.journal {
+            Some(_) => Some(self.lock()?),
+            None => None,
+        };
+        let todo = self.read()?;
+        self.last = todo.clone();
+        Ok(todo)
+    }
+
+    // 保存任务列表：加锁后重新读取磁盘上的版本，和其他进程的修改合并后再写入，
+    // 合并的结果会写回 todo。开启 git 时用本进程的改动作为提交说明
+    pub fn save(&mut self, todo: &mut TodoList) -> Result<(), TodoError> {
+        let _lock = self.lock()?;
+        let disk = self.read()?;
+        if let Some(disk) = &disk {
+            merge::merge(self.last.as_ref(), todo, disk);
+        }
+        sync::touch(disk.as_ref(), todo);
+        let redact = self.encrypted();
+        let message = history::describe_changes(disk.as_ref(), todo, redact);
+        match &disk {
+            Some(disk) if self.journal.is_some() && self.journal_entries < self.snapshot_every => {
+                self.append(disk, todo, &message)
+            }
+            _ => self.write(todo, &message),
+        }
+    }
+
+    // 恢复到 git 历史中的某个版本，恢复本身也作为一次新的提交
+    pub fn restore(&mut self, rev: &str) -> Result<TodoList, TodoError> {
+        if !self.git {
+            return Err(TodoError::Git("未开启 storage.git".to_string()));
+        }
+        let _lock = self.lock()?;
+        // 历史版本可能是用以前的口令加密的，解密时换上的密钥只用来读取，写入仍用当前的口令
+        let current = self.sealer.clone();
+        let restored = self.read_revision(rev);
+        self.sealer = current;
+        let todo = restored?;
+        self.write(&todo, &format!("restore {}", rev))?;
+        Ok(todo)
+    }
+
+    // 读取 git 历史中的某个版本
+    fn read_revision(&mut self, rev: &str) -> Result<TodoList, TodoError> {
+        let mut todo = self.parse(&history::show(&self.path, rev)?)?;
+        // 日志模式下还要回放当时的日志，那个版本可能还没有日志文件
+        if let Some(journal) = self.journal.clone()
+            && let Ok(content) = history::show(&journal, rev)
+        {
+            self.replay(&mut todo, &content)?;
+        }
+        Ok(todo)
+    }
+
+    pub fn log(&self, limit: usize) -> Result<String, TodoError> {
+        history::log(&self.files(), limit)
+    }
+
+    // 写一次快照并清空日志
+    pub fn compact(&mut self, todo: &mut TodoList) -> Result<(), TodoError> {
+        let _lock = self.lock()?;
+        if let Some(disk) = self.read()? {
+            merge::merge(self.last.as_ref(), todo, &disk);
+        }
+        self.write(todo, "compact")
+    }
+
+    // 受 git 管理的文件
+    fn files(&self) -> Vec<&Path> {
+        let mut files = vec![self.path.as_path()];
+        files.extend(self.journal.as_deref().filter(|journal| journal.exists()));
+        files
+    }
+
+    // 设置或修改口令，未加密的数据从此开始加密
+    pub fn change_passphrase(&mut self, todo: &mut TodoList) -> Result<(), TodoError> {
+        let passphrase = crypto::read_new_passphrase()?;
+        let _lock = self.lock()?;
+        if let Some(disk) = self.read()? {
+            merge::merge(self.last.as_ref(), todo, &disk);
+        }
+        self.sealer = Some(Sealer::new(&passphrase)?);
+        self.write(todo, "change passphrase")
+    }
+
+    // 在数据文件旁边的 .lock 文件上加排他锁，返回的文件关闭时自动解锁
+    fn lock(&self) -> Result<File, TodoError> {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(with_suffix(&self.path, ".lock"))?;
+        file.lock()?;
+        Ok(file)
+    }
+
+    // 已发出的提醒记录，放在数据文件旁边
+    pub fn reminders_path(&self) -> PathBuf {
+        with_suffix(&self.path, ".reminders")
+    }
+
+    // 和其他数据文件同步的记录，放在数据文件旁边
+    pub fn sync_path(&self) -> PathBuf {
+        with_suffix(&self.path, ".sync")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // 读取磁盘上的当前版本，日志模式下在快照上回放日志
+    fn read(&mut self) -> Result<Option<TodoList>, TodoError> {
+        let mut todo = match self.path.exists() {
+            true => Some(self.parse(&fs::read_to_string(&self.path)?)?),
+            false => None,
+        };
+        self.journal_entries = 0;
+        self.journal_len = 0;
+        if let Some(journal) = self.journal.clone()
+            && journal.exists()
+        {
+            let content = fs::read_to_string(&journal)?;
+            if !content.is_empty() {
+                let todo = todo.get_or_insert_with(TodoList::new);
+                self.replay(todo, &content)?;
+            }
+        }
+        Ok(todo)
+    }
+
+    // 回放日志内容；最后一行不完整（例如写入时崩溃）时忽略它
+    fn replay(&mut self, todo: &mut TodoList, content: &str) -> Result<(), TodoError> {
+        let mut offset = 0;
+        let lines: Vec<&str> = content.split_inclusive('\n').collect();
+        for (i, line) in lines.iter().enumerate() {
+            let entry = match self.parse_entry(line) {
+                Ok(entry) => entry,
+                Err(TodoError::Parse(_)) if i + 1 == lines.len() => {
+                    // 同一次运行中只提示一次
+                    if self.last.is_none() {
+                        println!("⚠️ 日志最后一行不完整，已忽略");
+                    }
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            journal::apply(todo, entry.event);
+            offset += line.len();
+            self.journal_entries += 1;
+        }
+        self.journal_len = offset as u64;
+        Ok(())
+    }
+
+    fn parse_entry(&mut self, line: &str) -> Result<Entry, TodoError> {
+        let mut value: Value = serde_json::from_str(line)?;
+        if crypto::is_sealed(&value) {
+            value = serde_json::from_str(&crypto::open(value, &mut self.sealer)?)?;
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    // 开启了加密，或者读到的文件本来就是加密的
+    fn encrypted(&self) -> bool {
+        self.encrypt || self.sealer.is_some()
+    }
+
+    // 解析数据文件内容，加密的文件先解密
+    fn parse(&mut self, content: &str) -> Result<TodoList, TodoError> {
+        let mut value: Value = serde_json::from_str(content)?;
+        if crypto::is_sealed(&value) {
+            value = serde_json::from_str(&crypto::open(value, &mut self.sealer)?)?;
+        }
+        migrate(&mut value);
+        Ok(serde_json::from_value(value)?)
+    }
+
+    // 需要加密时加密，第一次加密时设置口令
+    fn seal(&mut self, content: String) -> Result<String, TodoError> {
+        if !self.encrypted() {
+            return Ok(content);
+        }
+        let sealer = match self.sealer.take() {
+            Some(sealer) => sealer,
+            None => Sealer::new(&crypto::read_new_passphrase()?)?,
+        };
+        let sealed = sealer.seal(&content);
+        self.sealer = Some(sealer);
+        sealed
+    }
+
+    // 写入完整的数据文件，日志模式下同时清空日志
+    fn write(&mut self, todo: &TodoList, message: &str) -> Result<(), TodoError> {
+        let content = self.seal(serde_json::to_string_pretty(todo)?)?;
+        write_file(&self.path, &content)?;
+        // 快照写好之后才清空日志，中途崩溃时回放旧日志的结果也不变
+        if let Some(journal) = &self.journal
+            && journal.exists()
+        {
+            File::create(journal)?;
+        }
+        self.journal_entries = 0;
+        self.journal_len = 0;
+        self.committed(todo, message)
+    }
+
+    // 只把和磁盘版本不同的部分追加到日志
+    fn append(&mut self, disk: &TodoList, todo: &TodoList, message: &str) -> Result<(), TodoError> {
+        let Some(path) = self.journal.clone() else {
+            return self.write(todo, message);
+        };
+        let mut lines = String::new();
+        let events = journal::diff(disk, todo);
+        let count = events.len();
+        for event in events {
+            let line = self.seal(serde_json::to_string(&Entry::new(event))?)?;
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        // 去掉上次崩溃留下的不完整的行
+        if file.metadata()?.len() > self.journal_len {
+            file.set_len(self.journal_len)?;
+        }
+        file.write_all(lines.as_bytes())?;
+        file.sync_data()?;
+        self.journal_entries += count;
+        self.journal_len += lines.len() as u64;
+        self.committed(todo, message)
+    }
+
+    fn committed(&mut self, todo: &TodoList, message: &str) -> Result<(), TodoError> {
+        if self.git {
+            history::commit(&self.files(), message)?;
+        }
+        self.last = Some(todo.clone());
+        Ok(())
+    }
+}
+
+// 先写临时文件再改名，避免其他进程读到写了一半的文件
+fn write_file(path: &Path, content: &str) -> Result<(), TodoError> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+// 兼容旧数据：completed 布尔值迁移为 status 字段；现在的 completed 是完成时间，保留不动
+fn migrate(value: &mut Value) {
+    let Some(tasks) = value.get_mut("tasks").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for task in tasks {
+        let Some(task) = task.as_object_mut() else {
+            continue;
+        };
+        let Some(completed) = task.get("completed").and_then(Value::as_bool) else {
+            continue;
+        };
+        task.remove("completed");
+        if !task.contains_key("status") {
+            let status = if completed { "done" } else { "todo" };
+            task.insert("status".to_string(), Value::from(status));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::task::TaskEdit;
+
+    // 口令通过环境变量提供；只在这一个测试中设置，避免和其他测试互相影响
+    fn set_env(key: &str, value: &str) {
+        unsafe { env::set_var(key, value) };
+    }
+
+    #[test]
+    fn migrate_converts_the_old_completed_flag() {
+        let mut value = serde_json::json!({"tasks": [
+            {"id": 1, "completed": true},
+            {"id": 2, "completed": false},
+            {"id": 3, "status": "done", "completed": "2024-05-01T18:00:00"},
+        ]});
+        migrate(&mut value);
+        assert_eq!(value["tasks"][0], serde_json::json!({"id": 1, "status": "done"}));
+        assert_eq!(value["tasks"][1], serde_json::json!({"id": 2, "status": "todo"}));
+        assert_eq!(value["tasks"][2]["completed"], "2024-05-01T18:00:00");
+    }
+
+    #[test]
+    fn restore_keeps_the_current_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config {
+            data_file: Some(dir.path().join("todo.json")),
+            ..Config::default()
+        };
+        config.storage.git = true;
+        config.storage.encrypt = true;
+
+        let mut store = Store::new(&config);
+        let mut todo = TodoList::new();
+        todo.add_task("旧口令时的任务".to_string(), "high").unwrap();
+        set_env("TODO_LIST_NEW_PASSPHRASE", "old");
+        store.save(&mut todo).unwrap();
+        set_env("TODO_LIST_NEW_PASSPHRASE", "new");
+        store.change_passphrase(&mut todo).unwrap();
+
+        // 恢复用旧口令加密的版本，需要输入旧口令解密
+        set_env("TODO_LIST_PASSPHRASE", "old");
+        store.restore("HEAD~1").unwrap();
+
+        // 恢复后的文件仍然用新口令加密
+        set_env("TODO_LIST_PASSPHRASE", "new");
+        let restored = Store::new(&config).load().unwrap().unwrap();
+        assert_eq!(restored.get(1).unwrap().description, "旧口令时的任务");
+        set_env("TODO_LIST_PASSPHRASE", "old");
+        assert!(matches!(Store::new(&config).load(), Err(TodoError::WrongPassphrase)));
+    }
+
+    // 日志模式的存储，写满 snapshot_every 条事件后下次保存时写快照
+    fn journal_store(dir: &Path, snapshot_every: usize) -> (Config, Store) {
+        let mut config = Config {
+            data_file: Some(dir.join("todo.json")),
+            ..Config::default()
+        };
+        config.storage.journal = true;
+        config.storage.snapshot_every = snapshot_every;
+        let store = Store::new(&config);
+        (config, store)
+    }
+
+    fn json(todo: &TodoList) -> Value {
+        serde_json::to_value(todo).unwrap()
+    }
+
+    fn journal_lines(config: &Config) -> usize {
+        let journal = with_suffix(&config.data_file(), ".journal");
+        fs::read_to_string(journal).map_or(0, |content| content.lines().count())
+    }
+
+    #[test]
+    fn replaying_the_journal_over_the_snapshot_restores_the_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let (config, mut store) = journal_store(dir.path(), 100);
+        let mut todo = TodoList::new();
+        todo.add_task("写进快照".to_string(), "medium").unwrap();
+        store.save(&mut todo).unwrap();
+        let snapshot = fs::read_to_string(config.data_file()).unwrap();
+
+        todo.add_task("写进日志".to_string(), "high").unwrap();
+        store.save(&mut todo).unwrap();
+        todo.edit_task(
+            1,
+            TaskEdit {
+                description: Some("改过的描述".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        store.save(&mut todo).unwrap();
+        todo.delete_task(2).unwrap();
+        store.save(&mut todo).unwrap();
+
+        // 快照没有变，修改都在日志里
+        assert_eq!(fs::read_to_string(config.data_file()).unwrap(), snapshot);
+        assert!(journal_lines(&config) >= 4);
+        let loaded = Store::new(&config).load().unwrap().unwrap();
+        assert_eq!(json(&loaded), json(&todo));
+        assert_eq!(loaded.get(1).unwrap().description, "改过的描述");
+        assert!(loaded.get(2).is_none());
+    }
+
+    #[test]
+    fn a_truncated_last_line_is_skipped_and_removed_on_the_next_append() {
+        let dir = tempfile::tempdir().unwrap();
+        let (config, mut store) = journal_store(dir.path(), 100);
+        let mut todo = TodoList::new();
+        todo.add_task("快照".to_string(), "medium").unwrap();
+        store.save(&mut todo).unwrap();
+        todo.add_task("日志".to_string(), "medium").unwrap();
+        store.save(&mut todo).unwrap();
+        let before = json(&todo);
+
+        // 模拟写到一半时崩溃
+        let journal = with_suffix(&config.data_file(), ".journal");
+        let mut file = OpenOptions::new().append(true).open(&journal).unwrap();
+        file.write_all(br#"{"time":"2024-05-01T10:00:00","event":"put","task":{"id":3,"#).unwrap();
+
+        let mut store = Store::new(&config);
+        let mut todo = store.load().unwrap().unwrap();
+        assert_eq!(json(&todo), before);
+
+        todo.add_task("崩溃之后".to_string(), "medium").unwrap();
+        store.save(&mut todo).unwrap();
+        let content = fs::read_to_string(&journal).unwrap();
+        assert!(content.ends_with('\n'));
+        for line in content.lines() {
+            serde_json::from_str::<Value>(line).unwrap();
+        }
+        let loaded = Store::new(&config).load().unwrap().unwrap();
+        assert_eq!(json(&loaded), json(&todo));
+        assert_eq!(loaded.tasks.len(), 3);
+    }
+
+    #[test]
+    fn compaction_writes_a_snapshot_and_clears_the_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let (config, mut store) = journal_store(dir.path(), 3);
+        let mut todo = TodoList::new();
+        todo.add_task("第一个".to_string(), "medium").unwrap();
+        store.save(&mut todo).unwrap();
+        assert_eq!(journal_lines(&config), 0);
+
+        // 每次添加记两条事件：任务和下一个编号
+        todo.add_task("第二个".to_string(), "medium").unwrap();
+        store.save(&mut todo).unwrap();
+        assert_eq!(journal_lines(&config), 2);
+        todo.add_task("第三个".to_string(), "medium").unwrap();
+        store.save(&mut todo).unwrap();
+        assert_eq!(journal_lines(&config), 4);
+
+        // 已有 4 条，达到 snapshot_every，这次保存写快照
+        todo.add_task("第四个".to_string(), "medium").unwrap();
+        store.save(&mut todo).unwrap();
+        assert_eq!(journal_lines(&config), 0);
+        let snapshot: TodoList = serde_json::from_str(&fs::read_to_string(config.data_file()).unwrap()).unwrap();
+        assert_eq!(json(&snapshot), json(&todo));
+        let loaded = Store::new(&config).load().unwrap().unwrap();
+        assert_eq!(json(&loaded), json(&todo));
+
+        // 手动压缩的结果也一样
+        todo.add_task("第五个".to_string(), "medium").unwrap();
+        store.save(&mut todo).unwrap();
+        assert_eq!(journal_lines(&config), 2);
+        store.compact(&mut todo).unwrap();
+        assert_eq!(journal_lines(&config), 0);
+        let loaded = Store::new(&config).load().unwrap().unwrap();
+        assert_eq!(json(&loaded), json(&todo));
+        assert_eq!(loaded.next_id, 6);
+    }
+}
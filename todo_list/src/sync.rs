@@ -0,0 +1,555 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, Config};
+use crate::date;
+use crate::error::TodoError;
+use crate::storage::Store;
+use crate::task::Task;
+use crate::taskwarrior;
+use crate::todo::TodoList;
+
+// 内容有改动、修改时间却没变的任务需要记下修改时间。只补上 uuid 不算修改
+fn needs_touch(old: &Task, task: &Task) -> bool {
+    if old == task || old.modified != task.modified {
+        return false;
+    }
+    old.uuid == task.uuid
+        || *old
+            != Task {
+                uuid: old.uuid.clone(),
+                ..task.clone()
+            }
+}
+
+// 保存前和磁盘上的版本比较，给本进程改过的任务更新修改时间
+pub fn touch(disk: Option<&TodoList>, todo: &mut TodoList) {
+    let changed: Vec<usize> = todo
+        .tasks
+        .iter()
+        .filter(|task| match disk.and_then(|disk| disk.get(task.id)) {
+            Some(old) => needs_touch(old, task),
+            None => task.modified.is_none(),
+        })
+        .map(|task| task.id)
+        .collect();
+    let now = date::now();
+    for id in changed {
+        todo.tasks.update(id, |task| task.modified = Some(now));
+    }
+}
+
+// 冲突的处理方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolve {
+    Ask,
+    Local,
+    Remote,
+}
+
+// 上次同步后每个任务的修改时间，按对方文件分别记录，用来判断之后哪一边改过
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    #[serde(default)]
+    remotes: BTreeMap<String, BTreeMap<String, Option<NaiveDateTime>>>,
+}
+
+impl SyncState {
+    fn load(path: &Path) -> Result<SyncState, TodoError> {
+        if !path.exists() {
+            return Ok(SyncState::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    fn save(&self, path: &Path) -> Result<(), TodoError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+// 用绝对路径区分不同的对方文件，文件还不存在时按所在目录计算
+fn remote_key(path: &Path) -> String {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    dir.join(path.file_name().unwrap_or_default()).display().to_string()
+}
+
+// 同步前的准备：没有 uuid 的任务补上 uuid，两边编号和创建时间都相同的视为同一个任务；
+// 没有修改时间的旧任务以创建时间作为修改时间
+fn prepare(local: &mut TodoList, remote: &mut TodoList) {
+    let unmatched: HashMap<usize, NaiveDateTime> = remote
+        .tasks
+        .iter()
+        .filter(|task| task.uuid.is_none())
+        .map(|task| (task.id, task.created))
+        .collect();
+    let mut shared: HashMap<usize, String> = HashMap::new();
+    local.tasks.update_all(|task| {
+        if task.uuid.is_none() {
+            let uuid = taskwarrior::new_uuid();
+            if unmatched.get(&task.id) == Some(&task.created) {
+                shared.insert(task.id, uuid.clone());
+            }
+            task.uuid = Some(uuid);
+        }
+        task.modified.get_or_insert(task.created);
+    });
+    remote.tasks.update_all(|task| {
+        if task.uuid.is_none() {
+            task.uuid = Some(shared.remove(&task.id).unwrap_or_else(taskwarrior::new_uuid));
+        }
+        task.modified.get_or_insert(task.created);
+    });
+}
+
+fn uuid(task: &Task) -> &str {
+    task.uuid.as_deref().unwrap_or_default()
+}
+
+// 编号 -> uuid
+fn uuids(todo: &TodoList) -> HashMap<usize, &str> {
+    todo.tasks.iter().map(|task| (task.id, uuid(task))).collect()
+}
+
+// 依赖和父任务换成 uuid，两个文件中的编号可能不同
+fn references<'a>(task: &Task, ids: &HashMap<usize, &'a str>) -> (Vec<Option<&'a str>>, Option<Option<&'a str>>) {
+    let depends = task.depends.iter().map(|dep| ids.get(dep).copied()).collect();
+    (depends, task.parent.map(|parent| ids.get(&parent).copied()))
+}
+
+// 不看编号和修改时间比较两边的内容
+fn same_content(a: &Task, a_ids: &HashMap<usize, &str>, b: &Task, b_ids: &HashMap<usize, &str>) -> bool {
+    let strip = |task: &Task| Task {
+        id: 0,
+        modified: None,
+        depends: Vec::new(),
+        parent: None,
+        ..task.clone()
+    };
+    references(a, a_ids) == references(b, b_ids) && strip(a) == strip(b)
+}
+
+// 一个任务同步后的结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pick {
+    Local,
+    Remote,
+    Delete,
+}
+
+struct Item<'a> {
+    local: Option<&'a Task>,
+    remote: Option<&'a Task>,
+    same: bool,
+    // None 表示两边都改过，需要选择
+    pick: Option<Pick>,
+}
+
+impl Item<'_> {
+    fn task(&self) -> &Task {
+        self.local.or(self.remote).expect("至少一边有任务")
+    }
+
+    fn uuid(&self) -> &str {
+        uuid(self.task())
+    }
+}
+
+// 按上次同步时的修改时间判断：只有一边改过时取改过的一边，删除也算改动；
+// 两边都改过（或者从没同步过却内容不同）时是冲突
+fn decide(local: Option<&Task>, remote: Option<&Task>, same: bool, base: Option<Option<NaiveDateTime>>) -> Option<Pick> {
+    let changed = |task: &Task| base.is_none_or(|base| task.modified != base);
+    match (local, remote, base) {
+        (Some(_), Some(_), _) if same => Some(Pick::Local),
+        (Some(_), Some(_), None) => None,
+        (Some(local), Some(remote), Some(_)) => match (changed(local), changed(remote)) {
+            (true, true) => None,
+            (false, true) => Some(Pick::Remote),
+            _ => Some(Pick::Local),
+        },
+        (Some(_), None, None) => Some(Pick::Local),
+        (None, Some(_), None) => Some(Pick::Remote),
+        (Some(task), None, Some(_)) | (None, Some(task), Some(_)) if changed(task) => None,
+        _ => Some(Pick::Delete),
+    }
+}
+
+// 冲突时显示的字段
+fn describe(cfg: &Config, task: &Task) -> Vec<(&'static str, String)> {
+    let tags: Vec<String> = task.tags.iter().map(|tag| format!("#{}", tag)).collect();
+    vec![
+        ("描述", task.description.clone()),
+        ("状态", task.status.to_string().to_string()),
+        ("优先级", task.priority.to_string().to_string()),
+        ("截止", task.due.map(|due| cfg.format_due(due)).unwrap_or_default()),
+        ("标签", tags.join(" ")),
+        ("项目", task.project.clone().unwrap_or_default()),
+        ("预估", task.estimate.map(|e| e.to_string()).unwrap_or_default()),
+        ("备注", task.notes.replace('\n', " / ")),
+        ("修改时间", task.modified.map(|m| cfg.format_datetime(m)).unwrap_or_default()),
+    ]
+}
+
+// 显示冲突中两边不同的地方
+fn show_conflict(cfg: &Config, item: &Item) {
+    println!("\n⚠️ 冲突：任务「{}」在上次同步后两边都改过", item.task().description);
+    let modified = |task: &Task| task.modified.map(|m| cfg.format_datetime(m)).unwrap_or_default();
+    match (item.local, item.remote) {
+        (Some(local), Some(remote)) => {
+            for ((label, l), (_, r)) in describe(cfg, local).iter().zip(&describe(cfg, remote)) {
+                if l != r {
+                    println!("  {}：本地「{}」 / 远端「{}」", label, l, r);
+                }
+            }
+        }
+        (Some(local), None) => println!("  远端已删除，本地修改于 {}", modified(local)),
+        (None, Some(remote)) => println!("  本地已删除，远端修改于 {}", modified(remote)),
+        (None, None) => {}
+    }
+}
+
+// 询问保留哪一边，返回 None 表示取消同步
+fn ask(cfg: &Config, item: &Item) -> Option<Pick> {
+    show_conflict(cfg, item);
+    let side = |task: Option<&Task>, pick: Pick| if task.is_some() { pick } else { Pick::Delete };
+    loop {
+        print!("保留哪一边？[l] 本地 / [r] 远端 / [q] 取消同步：");
+        let _ = io::stdout().flush();
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+            return None;
+        }
+        match input.trim().to_lowercase().as_str() {
+            "l" | "local" | "本地" => return Some(side(item.local, Pick::Local)),
+            "r" | "remote" | "远端" => return Some(side(item.remote, Pick::Remote)),
+            "q" | "quit" | "取消" => return None,
+            _ => println!("🙅请输入 l、r 或 q"),
+        }
+    }
+}
+
+// 和另一个数据文件（例如共享目录中的副本）双向同步，两边写入相同的结果。
+// 共享目录中的文件只写快照，不使用 git 和日志
+pub fn sync(
+    todo: &mut TodoList,
+    store: &mut Store,
+    remote_path: &Path,
+    resolve: Resolve,
+    dry_run: bool,
+) -> Result<(), TodoError> {
+    let remote_path: PathBuf = config::expand_home(remote_path);
+    let key = remote_key(&remote_path);
+    if key == remote_key(store.path()) {
+        return Err(TodoError::Sync("不能和数据文件自己同步".to_string()));
+    }
+    let mut cfg = todo.config.clone();
+    cfg.data_file = Some(remote_path.clone());
+    cfg.storage.git = false;
+    cfg.storage.journal = false;
+    let mut remote_store = Store::new(&cfg);
+    let mut remote = remote_store.load()?.unwrap_or_else(TodoList::new);
+    let mut local = todo.clone();
+    prepare(&mut local, &mut remote);
+
+    let state_path = store.sync_path();
+    let mut state = SyncState::load(&state_path)?;
+    let base = state.remotes.get(&key).cloned().unwrap_or_default();
+
+    let (local_ids, remote_ids) = (uuids(&local), uuids(&remote));
+    let local_by_uuid: HashMap<&str, &Task> = local.tasks.iter().map(|task| (uuid(task), task)).collect();
+    let remote_by_uuid: HashMap<&str, &Task> = remote.tasks.iter().map(|task| (uuid(task), task)).collect();
+    let item = |local: Option<&'_ Task>, remote: Option<&'_ Task>| {
+        let task = local.or(remote).expect("至少一边有任务");
+        let same = match (local, remote) {
+            (Some(l), Some(r)) => same_content(l, &local_ids, r, &remote_ids),
+            _ => false,
+        };
+        let pick = decide(local, remote, same, base.get(uuid(task)).copied());
+        (same, pick)
+    };
+    let mut items = Vec::new();
+    for task in &local.tasks {
+        let remote = remote_by_uuid.get(uuid(task)).copied();
+        let (same, pick) = item(Some(task), remote);
+        items.push(Item { local: Some(task), remote, same, pick });
+    }
+    for task in remote.tasks.iter().filter(|task| !local_by_uuid.contains_key(uuid(task))) {
+        let (same, pick) = item(None, Some(task));
+        items.push(Item { local: None, remote: Some(task), same, pick });
+    }
+
+    let conflicts = items.iter().filter(|item| item.pick.is_none()).count();
+    if dry_run {
+        for item in &items {
+            let task = match item.pick {
+                Some(Pick::Remote) => item.remote.unwrap_or(item.task()),
+                _ => item.task(),
+            };
+            match item.pick {
+                None => show_conflict(&todo.config, item),
+                Some(Pick::Remote) => println!("⬇️ #{} {}", task.id, task.description),
+                Some(Pick::Local) if !item.same => println!("⬆️ #{} {}", task.id, task.description),
+                Some(Pick::Delete) => println!("🗑️ #{} {}", task.id, task.description),
+                Some(Pick::Local) => {}
+            }
+        }
+        println!("\n🔍 预览：和 {} 同步，冲突 {} 个", remote_path.display(), conflicts);
+        return Ok(());
+    }
+    // 不能询问时不写任何一边，由用户指定保留哪一边
+    if conflicts > 0 && resolve == Resolve::Ask && !todo.config.interactive {
+        return Err(TodoError::NotInteractive(format!(
+            "有 {} 个冲突，请加上 --ours 或 --theirs 指定保留哪一边",
+            conflicts
+        )));
+    }
+    for item in items.iter_mut().filter(|item| item.pick.is_none()) {
+        item.pick = match resolve {
+            Resolve::Local => Some(if item.local.is_some() { Pick::Local } else { Pick::Delete }),
+            Resolve::Remote => Some(if item.remote.is_some() { Pick::Remote } else { Pick::Delete }),
+            Resolve::Ask => match ask(&todo.config, item) {
+                Some(pick) => Some(pick),
+                None => {
+                    println!("🚫 已取消同步，两边都没有修改");
+                    return Ok(());
+                }
+            },
+        };
+    }
+
+    // 本地已有的任务保留本地编号，远端新加的任务编号被占用时重新分配
+    let mut next_id = local.next_id.max(remote.next_id);
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    let mut used = HashSet::new();
+    for item in items.iter().filter(|item| item.pick != Some(Pick::Delete)) {
+        if let Some(task) = item.local {
+            ids.insert(item.uuid(), task.id);
+            used.insert(task.id);
+        }
+    }
+    for item in items.iter().filter(|item| item.pick == Some(Pick::Remote) && item.local.is_none()) {
+        let mut id = item.task().id;
+        if !used.insert(id) {
+            println!("🔀 编号 #{} 已被本地任务使用，远端任务改为 #{}", id, next_id);
+            id = next_id;
+            used.insert(id);
+            next_id += 1;
+        }
+        ids.insert(item.uuid(), id);
+    }
+
+    let (mut pulled, mut pushed, mut deleted) = (0, 0, 0);
+    let mut tasks = Vec::new();
+    for item in &items {
+        let (task, from) = match item.pick {
+            Some(Pick::Local) => {
+                pushed += usize::from(!item.same);
+                (item.local, &local_ids)
+            }
+            Some(Pick::Remote) => {
+                pulled += 1;
+                (item.remote, &remote_ids)
+            }
+            _ => {
+                deleted += 1;
+                continue;
+            }
+        };
+        let Some(task) = task else {
+            continue;
+        };
+        let mut task = task.clone();
+        let id = |old: &usize| from.get(old).and_then(|uuid| ids.get(uuid)).copied();
+        task.id = ids[item.uuid()];
+        task.depends = task.depends.iter().filter_map(id).collect();
+        task.parent = task.parent.as_ref().and_then(id);
+        tasks.push(task);
+    }
+    let mut result = TodoList::new();
+    result.tasks = tasks.into_iter().collect();
+    result.next_id = next_id.max(result.tasks.max_id().map_or(1, |id| id + 1));
+    result.config = todo.config.clone();
+
+    *todo = result.clone();
+    store.save(todo)?;
+    remote_store.save(&mut result)?;
+    let synced = result
+        .tasks
+        .iter()
+        .filter_map(|task| Some((task.uuid.clone()?, task.modified)))
+        .collect();
+    state.remotes.insert(key, synced);
+    state.save(&state_path)?;
+
+    if pulled + pushed + deleted == 0 {
+        println!("✅ 已和 {} 同步，两边没有差异", remote_path.display());
+    } else {
+        println!(
+            "🔄 已和 {} 同步：取来 {} 个，推送 {} 个，删除 {} 个，解决冲突 {} 个",
+            remote_path.display(),
+            pulled,
+            pushed,
+            deleted,
+            conflicts
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    fn at(day: u32) -> Option<NaiveDateTime> {
+        chrono::NaiveDate::from_ymd_opt(2024, 5, day).unwrap().and_hms_opt(9, 0, 0)
+    }
+
+    fn task(modified: u32) -> Task {
+        Task {
+            modified: at(modified),
+            ..Task::new(1, "任务".to_string(), Priority::Medium)
+        }
+    }
+
+    #[test]
+    fn decide_takes_the_side_that_changed() {
+        let (old, new) = (task(1), task(2));
+        let base = Some(at(1));
+        assert_eq!(decide(Some(&old), Some(&old), true, base), Some(Pick::Local));
+        assert_eq!(decide(Some(&new), Some(&old), false, base), Some(Pick::Local));
+        assert_eq!(decide(Some(&old), Some(&new), false, base), Some(Pick::Remote));
+    }
+
+    #[test]
+    fn decide_reports_a_conflict_when_both_changed() {
+        let (local, remote) = (task(2), task(3));
+        assert_eq!(decide(Some(&local), Some(&remote), false, Some(at(1))), None);
+        // 两边改成了一样的内容不算冲突
+        assert_eq!(decide(Some(&local), Some(&remote), true, Some(at(1))), Some(Pick::Local));
+    }
+
+    #[test]
+    fn decide_handles_deletions() {
+        let (old, new) = (task(1), task(2));
+        let base = Some(at(1));
+        // 一边删除、另一边没改：删除
+        assert_eq!(decide(Some(&old), None, false, base), Some(Pick::Delete));
+        assert_eq!(decide(None, Some(&old), false, base), Some(Pick::Delete));
+        // 一边删除、另一边改过：冲突
+        assert_eq!(decide(Some(&new), None, false, base), None);
+        assert_eq!(decide(None, Some(&new), false, base), None);
+    }
+
+    #[test]
+    fn decide_handles_tasks_never_synced() {
+        let (local, remote) = (task(1), task(2));
+        assert_eq!(decide(Some(&local), None, false, None), Some(Pick::Local));
+        assert_eq!(decide(None, Some(&remote), false, None), Some(Pick::Remote));
+        assert_eq!(decide(Some(&local), Some(&remote), true, None), Some(Pick::Local));
+        assert_eq!(decide(Some(&local), Some(&remote), false, None), None);
+    }
+
+    struct Files {
+        _dir: tempfile::TempDir,
+        store: Store,
+        remote: Store,
+        remote_path: PathBuf,
+    }
+
+    fn files() -> Files {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(&Config {
+            data_file: Some(dir.path().join("todo.json")),
+            ..Config::default()
+        });
+        let remote_path = dir.path().join("shared.json");
+        let remote = Store::new(&Config {
+            data_file: Some(remote_path.clone()),
+            ..Config::default()
+        });
+        Files {
+            _dir: dir,
+            store,
+            remote,
+            remote_path,
+        }
+    }
+
+    fn local_list() -> TodoList {
+        let mut todo = TodoList::new();
+        todo.config.interactive = false;
+        todo
+    }
+
+    fn find<'a>(todo: &'a TodoList, description: &str) -> &'a Task {
+        todo.tasks.iter().find(|task| task.description == description).unwrap()
+    }
+
+    #[test]
+    fn sync_renumbers_colliding_new_tasks_and_keeps_links() {
+        let mut files = files();
+        let mut todo = local_list();
+        todo.add_task("共同的任务".to_string(), "medium").unwrap();
+        sync(&mut todo, &mut files.store, &files.remote_path, Resolve::Ask, false).unwrap();
+
+        todo.add_task("本地新任务".to_string(), "medium").unwrap();
+        let mut remote = files.remote.load().unwrap().unwrap();
+        remote.add_task("远端父任务".to_string(), "medium").unwrap();
+        remote.add_task("远端子任务".to_string(), "medium").unwrap();
+        remote.tasks.update(3, |task| {
+            task.parent = Some(2);
+            task.depends = vec![2];
+        });
+        files.remote.save(&mut remote).unwrap();
+
+        sync(&mut todo, &mut files.store, &files.remote_path, Resolve::Ask, false).unwrap();
+        assert_eq!(todo.tasks.len(), 4);
+        assert_eq!(find(&todo, "共同的任务").id, 1);
+        assert_eq!(find(&todo, "本地新任务").id, 2);
+        let parent = find(&todo, "远端父任务");
+        let child = find(&todo, "远端子任务");
+        assert_eq!(parent.id, 4);
+        assert_eq!(child.id, 3);
+        assert_eq!(child.parent, Some(parent.id));
+        assert_eq!(child.depends, vec![parent.id]);
+        assert!(todo.next_id > 4);
+
+        // 对方文件写入相同的结果
+        let remote = files.remote.load().unwrap().unwrap();
+        assert_eq!(find(&remote, "远端子任务").parent, Some(4));
+        assert_eq!(find(&remote, "本地新任务").id, 2);
+    }
+
+    #[test]
+    fn sync_refuses_to_ask_without_a_terminal() {
+        let mut files = files();
+        let mut todo = local_list();
+        todo.add_task("原来的描述".to_string(), "medium").unwrap();
+        sync(&mut todo, &mut files.store, &files.remote_path, Resolve::Ask, false).unwrap();
+
+        let mut remote = files.remote.load().unwrap().unwrap();
+        remote.tasks.update(1, |task| {
+            task.description = "远端改的".to_string();
+            task.modified = at(1);
+        });
+        files.remote.save(&mut remote).unwrap();
+        todo.tasks.update(1, |task| {
+            task.description = "本地改的".to_string();
+            task.modified = at(2);
+        });
+
+        let result = sync(&mut todo, &mut files.store, &files.remote_path, Resolve::Ask, false);
+        assert!(matches!(result, Err(TodoError::NotInteractive(_))));
+        assert_eq!(files.remote.load().unwrap().unwrap().tasks.get(1).unwrap().description, "远端改的");
+
+        sync(&mut todo, &mut files.store, &files.remote_path, Resolve::Remote, false).unwrap();
+        assert_eq!(todo.tasks.get(1).unwrap().description, "远端改的");
+    }
+}
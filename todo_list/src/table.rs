@@ -0,0 +1,359 @@
+use std::env;
+use std::io::{IsTerminal, Write};
+
+use terminal_size::{Width, terminal_size};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::config::Config;
+
+// 列之间的分隔符
+const SEPARATOR: &str = " | ";
+// 可收缩的列最少保留的宽度
+const MIN_FLEX_WIDTH: usize = 8;
+
+// 终端宽度：优先使用 COLUMNS，输出不是终端时不限制宽度
+pub fn terminal_width() -> Option<usize> {
+    if let Some(columns) = env::var("COLUMNS").ok().and_then(|c| c.parse().ok()) {
+        return Some(columns);
+    }
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    terminal_size().map(|(Width(w), _)| w as usize)
+}
+
+// 截取到指定显示宽度（中文和 emoji 占两格），被截断时以 … 结尾
+pub fn truncate(s: &str, width: usize) -> String {
+    if s.width() <= width {
+        return s.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > width {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push('…');
+    out
+}
+
+// 按显示宽度补齐空格，超出时截断
+pub fn pad(s: &str, width: usize) -> String {
+    let s = truncate(s, width);
+    let fill = width.saturating_sub(s.width());
+    format!("{}{}", s, " ".repeat(fill))
+}
+
+// 按显示宽度折行，英文尽量在空格处断开
+pub fn wrap(s: &str, width: usize) -> Vec<String> {
+    let width = width.max(2);
+    let mut lines = Vec::new();
+    for paragraph in s.lines() {
+        let mut line = String::new();
+        let mut used = 0;
+        for c in paragraph.chars() {
+            let w = c.width().unwrap_or(0);
+            if used + w > width {
+                // 当前行里有空格时从最后一个空格处断开
+                match line.rfind(' ').filter(|&i| i > 0 && c != ' ') {
+                    Some(i) => {
+                        let rest = line.split_off(i);
+                        lines.push(line);
+                        line = rest.trim_start().to_string();
+                    }
+                    None => lines.push(std::mem::take(&mut line)),
+                }
+                used = line.width();
+                if c == ' ' && line.is_empty() {
+                    continue;
+                }
+            }
+            line.push(c);
+            used += w;
+        }
+        lines.push(line);
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+// 内容超出列宽时的处理方式；Fixed 列不参与收缩
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    Fixed,
+    Wrap,
+    Truncate,
+}
+
+#[derive(Debug, Clone)]
+struct Column {
+    header: String,
+    align: Align,
+    overflow: Overflow,
+}
+
+// 单元格：文字和可选的颜色名，宽度只按文字计算
+#[derive(Debug, Clone, Default)]
+pub struct Cell {
+    pub text: String,
+    pub color: Option<String>,
+}
+
+impl Cell {
+    pub fn colored(text: impl Into<String>, color: &str) -> Self {
+        Cell {
+            text: text.into(),
+            color: Some(color.to_string()),
+        }
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell { text, color: None }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::from(text.to_string())
+    }
+}
+
+// 按显示宽度对齐的表格，可以按终端宽度折行或截断
+pub struct Table<'a> {
+    config: &'a Config,
+    columns: Vec<Column>,
+    rows: Vec<Vec<Cell>>,
+    max_width: Option<usize>,
+}
+
+impl<'a> Table<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Table {
+            config,
+            columns: Vec::new(),
+            rows: Vec::new(),
+            max_width: terminal_width(),
+        }
+    }
+
+    pub fn column(mut self, header: &str, align: Align, overflow: Overflow) -> Self {
+        self.columns.push(Column {
+            header: header.to_string(),
+            align,
+            overflow,
+        });
+        self
+    }
+
+    pub fn row(&mut self, cells: Vec<Cell>) {
+        self.rows.push(cells);
+    }
+
+    // 计算每列宽度：先取内容的最大宽度，超出终端宽度时从最宽的可收缩列开始逐格收缩
+    fn widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, col)| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(i))
+                    .flat_map(|cell| cell.text.lines().map(|l| l.width()))
+                    .chain([col.header.width()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let Some(max_width) = self.max_width else {
+            return widths;
+        };
+        let separators = SEPARATOR.width() * self.columns.len().saturating_sub(1);
+        // 先收缩截断的列，再收缩折行的列
+        for overflow in [Overflow::Truncate, Overflow::Wrap] {
+            while widths.iter().sum::<usize>() + separators > max_width {
+                let widest = (0..self.columns.len())
+                    .filter(|&i| self.columns[i].overflow == overflow)
+                    .filter(|&i| widths[i] > MIN_FLEX_WIDTH)
+                    .max_by_key(|&i| widths[i]);
+                match widest {
+                    Some(i) => widths[i] -= 1,
+                    None => break,
+                }
+            }
+        }
+        widths
+    }
+
+    fn format_cell(&self, text: &str, color: Option<&str>, width: usize, align: Align) -> String {
+        let text = truncate(text, width);
+        let fill = " ".repeat(width.saturating_sub(text.width()));
+        let text = match color {
+            Some(color) => self.config.paint(&text, color),
+            None => text,
+        };
+        match align {
+            Align::Left => format!("{}{}", text, fill),
+            Align::Right => format!("{}{}", fill, text),
+        }
+    }
+
+    // 渲染一行，可折行的单元格拆成多行，行高取最多的那个
+    fn render_row(&self, cells: &[Cell], widths: &[usize], out: &mut Vec<String>) {
+        let lines: Vec<Vec<String>> = self
+            .columns
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (col, &w))| {
+                let text = cells.get(i).map(|c| c.text.as_str()).unwrap_or("");
+                match col.overflow {
+                    Overflow::Wrap => wrap(text, w),
+                    _ => vec![text.lines().next().unwrap_or("").to_string()],
+                }
+            })
+            .collect();
+        let height = lines.iter().map(Vec::len).max().unwrap_or(1);
+        for line in 0..height {
+            let parts: Vec<String> = self
+                .columns
+                .iter()
+                .zip(widths)
+                .enumerate()
+                .map(|(i, (col, &w))| {
+                    let text = lines[i].get(line).map(String::as_str).unwrap_or("");
+                    let color = cells.get(i).and_then(|c| c.color.as_deref());
+                    self.format_cell(text, color, w, col.align)
+                })
+                .collect();
+            out.push(parts.join(SEPARATOR).trim_end().to_string());
+        }
+    }
+
+    // 渲染成若干行文字，不含末尾换行
+    pub fn render(&self) -> Vec<String> {
+        let widths = self.widths();
+        let total = widths.iter().sum::<usize>() + SEPARATOR.width() * widths.len().saturating_sub(1);
+        let rule = "-".repeat(total);
+        let mut out = Vec::new();
+
+        let header: Vec<Cell> = self
+            .columns
+            .iter()
+            .map(|col| col.header.as_str().into())
+            .collect();
+        self.render_row(&header, &widths, &mut out);
+        out.push(rule.clone());
+        for row in &self.rows {
+            self.render_row(row, &widths, &mut out);
+        }
+        out.push(rule);
+        out
+    }
+
+    // 输出到标准输出；管道被提前关闭（例如 | head）时直接停止，不再 panic
+    pub fn print(&self) {
+        let mut stdout = std::io::stdout().lock();
+        for line in self.render() {
+            if writeln!(stdout, "{}", line).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        assert_eq!("写周报".width(), 6);
+        assert_eq!("🎯 go".width(), 5);
+        assert_eq!(pad("写周报", 8), "写周报  ");
+        assert_eq!(pad("abc", 8).width(), 8);
+        assert_eq!(pad("🎯", 3), "🎯 ");
+    }
+
+    #[test]
+    fn truncate_never_splits_a_character_or_overshoots() {
+        assert_eq!(truncate("写周报", 6), "写周报");
+        assert_eq!(truncate("写周报和月报", 7), "写周报…");
+        // 放不下整个汉字时宁可少一格
+        assert_eq!(truncate("写周报和月报", 6), "写周…");
+        assert_eq!(truncate("ab中文", 4), "ab…");
+        assert_eq!(truncate("🎯🎯🎯", 4), "🎯…");
+        assert_eq!(truncate("中文", 1), "…");
+        assert_eq!(truncate("中文", 0), "");
+        for width in 0..12 {
+            for s in ["写周报和月报", "mixed 中英 text", "🎯🚀✅ emoji"] {
+                let cut = truncate(s, width);
+                assert!(cut.width() <= width, "{} {}", s, width);
+                assert!(s.starts_with(cut.trim_end_matches('…')));
+            }
+        }
+    }
+
+    #[test]
+    fn wrap_keeps_lines_within_the_width() {
+        assert_eq!(wrap("写周报和月报", 5), vec!["写周", "报和", "月报"]);
+        assert_eq!(wrap("hello big world", 9), vec!["hello big", "world"]);
+        assert_eq!(wrap("第一行\nsecond", 20), vec!["第一行", "second"]);
+        assert_eq!(wrap("", 5), vec![""]);
+        for line in wrap("发布 v1.2 之前 review 一下 🎯 所有的 PR", 7) {
+            assert!(line.width() <= 7, "{}", line);
+        }
+    }
+
+    // 每行中分隔符所在的显示列
+    fn separator_columns(line: &str) -> Vec<usize> {
+        line.match_indices(SEPARATOR).map(|(i, _)| line[..i].width()).collect()
+    }
+
+    #[test]
+    fn columns_line_up_with_mixed_text() {
+        let config = Config::default();
+        let mut table = Table::new(&config)
+            .column("编号", Align::Right, Overflow::Fixed)
+            .column("描述", Align::Left, Overflow::Truncate)
+            .column("标签", Align::Left, Overflow::Fixed);
+        table.max_width = None;
+        table.row(vec!["1".into(), "Write report".into(), "work".into()]);
+        table.row(vec!["12".into(), "写周报 🎯".into(), "工作".into()]);
+        table.row(vec!["123".into(), "mixed 中英".into(), "-".into()]);
+        let lines = table.render();
+        let expected = separator_columns(&lines[0]);
+        assert_eq!(expected, vec![4, 19]);
+        for line in lines.iter().filter(|line| !line.starts_with('-')) {
+            assert_eq!(separator_columns(line), expected, "{}", line);
+        }
+        assert_eq!(lines[3], "  12 | 写周报 🎯    | 工作");
+
+        // 超出终端宽度时收缩描述列，每行都不超过宽度
+        table.row(vec!["4".into(), "很长很长很长很长很长很长的描述".into(), "x".into()]);
+        table.max_width = Some(24);
+        let lines = table.render();
+        for line in &lines {
+            assert!(line.width() <= 24, "{}", line);
+        }
+        assert!(lines.iter().any(|line| line.contains("很长很长…")));
+    }
+}
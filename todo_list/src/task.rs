@@ -0,0 +1,287 @@
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::date;
+use crate::estimate::Estimate;
+use crate::error::TodoError;
+
+// 定义优先级枚举
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    High,
+    Medium,
+    Low,
+}
+
+impl Priority {
+    pub fn to_string(self) -> &'static str {
+        match self {
+            Priority::High => "高",
+            Priority::Medium => "中",
+            Priority::Low => "低",
+        }
+    }
+
+    pub fn to_english(self) -> &'static str {
+        match self {
+            Priority::High => "high",
+            Priority::Medium => "medium",
+            Priority::Low => "low",
+        }
+    }
+
+    pub fn to_emoji(self) -> &'static str {
+        match self {
+            Priority::High => "🔴",
+            Priority::Medium => "🟡",
+            Priority::Low => "🟢",
+        }
+    }
+}
+
+impl FromStr for Priority {
+    type Err = TodoError;
+
+    fn from_str(s: &str) -> Result<Priority, TodoError> {
+        match s.to_lowercase().as_str() {
+            "high" | "高" | "h" | "1" => Ok(Priority::High),
+            "medium" | "中" | "m" | "2" => Ok(Priority::Medium),
+            "low" | "低" | "l" | "3" => Ok(Priority::Low),
+            _ => Err(TodoError::InvalidPriority(s.to_string())),
+        }
+    }
+}
+
+// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    Todo,
+    InProgress,
+    Waiting,
+    Blocked,
+    Done,
+    Cancelled,
+}
+
+impl Status {
+    // 看板中各列的顺序
+    pub const ALL: [Status; 6] = [
+        Status::Todo,
+        Status::InProgress,
+        Status::Waiting,
+        Status::Blocked,
+        Status::Done,
+        Status::Cancelled,
+    ];
+
+    pub fn to_string(self) -> &'static str {
+        match self {
+            Status::Todo => "待办",
+            Status::InProgress => "进行中",
+            Status::Waiting => "等待中",
+            Status::Blocked => "受阻",
+            Status::Done => "已完成",
+            Status::Cancelled => "已取消",
+        }
+    }
+
+    pub fn to_english(self) -> &'static str {
+        match self {
+            Status::Todo => "todo",
+            Status::InProgress => "in-progress",
+            Status::Waiting => "waiting",
+            Status::Blocked => "blocked",
+            Status::Done => "done",
+            Status::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn to_emoji(self) -> &'static str {
+        match self {
+            Status::Todo => "⏳",
+            Status::InProgress => "🚧",
+            Status::Waiting => "💤",
+            Status::Blocked => "⛔",
+            Status::Done => "✅",
+            Status::Cancelled => "❌",
+        }
+    }
+
+    // 已完成或已取消的任务不再需要处理
+    pub fn is_closed(self) -> bool {
+        matches!(self, Status::Done | Status::Cancelled)
+    }
+
+    // 允许的状态切换：受阻的任务要先解除阻塞才能完成，
+    // 已完成/已取消的任务只能重新打开为待办
+    pub fn can_transition_to(self, to: Status) -> bool {
+        use Status::*;
+        match (self, to) {
+            (from, to) if from == to => false,
+            (Todo | InProgress | Waiting, _) => true,
+            (Blocked, Done) => false,
+            (Blocked, _) => true,
+            (Done | Cancelled, Todo) => true,
+            (Done | Cancelled, _) => false,
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = TodoError;
+
+    fn from_str(s: &str) -> Result<Status, TodoError> {
+        match s.to_lowercase().as_str() {
+            "todo" | "待办" | "t" => Ok(Status::Todo),
+            "in-progress" | "doing" | "进行中" | "i" => Ok(Status::InProgress),
+            "waiting" | "等待" | "等待中" | "w" => Ok(Status::Waiting),
+            "blocked" | "受阻" | "b" => Ok(Status::Blocked),
+            "done" | "完成" | "已完成" | "d" => Ok(Status::Done),
+            "cancelled" | "canceled" | "取消" | "已取消" | "c" => Ok(Status::Cancelled),
+            _ => Err(TodoError::InvalidStatus(s.to_string())),
+        }
+    }
+}
+
+// 定义任务结构体
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Task {
+    pub id: usize,
+    pub description: String,
+    pub priority: Priority,
+    pub status: Status,
+    #[serde(default = "date::now")]
+    pub created: NaiveDateTime,
+    // 最后修改时间，保存时自动更新，同步时用来判断哪一边改过
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<NaiveDateTime>,
+    // 完成时间，重新打开时清除
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub due: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // 依赖的任务编号，这些任务没结束前本任务处于阻塞状态
+    #[serde(default)]
+    pub depends: Vec<usize>,
+    // 所属项目，可以用 / 分出层级，例如 周会/后端
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    // 父任务编号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<usize>,
+    // 工作量预估
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<Estimate>,
+    // 全局唯一编号，和 Taskwarrior 导入导出时使用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    // 多行备注
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+    // 完成的番茄钟，记录每个番茄的完成时间
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pomodoros: Vec<NaiveDateTime>,
+}
+
+impl Task {
+    pub fn new(id: usize, description: String, priority: Priority) -> Self {
+        Task {
+            id,
+            description,
+            priority,
+            status: Status::Todo,
+            created: date::now(),
+            modified: None,
+            completed: None,
+            due: None,
+            tags: Vec::new(),
+            depends: Vec::new(),
+            project: None,
+            parent: None,
+            estimate: None,
+            uuid: None,
+            notes: String::new(),
+            pomodoros: Vec::new(),
+        }
+    }
+}
+
+// 编辑任务时要修改的字段，None 表示保持不变
+#[derive(Debug, Default)]
+pub struct TaskEdit {
+    pub description: Option<String>,
+    pub priority: Option<Priority>,
+    pub due: Option<Option<NaiveDateTime>>,
+    pub tags: Option<Vec<String>>,
+    pub depends: Option<Vec<usize>>,
+    pub project: Option<Option<String>>,
+    pub parent: Option<Option<usize>>,
+    pub estimate: Option<Option<Estimate>>,
+    pub notes: Option<String>,
+}
+
+// 解析标签列表：逗号或空格分隔，可带 # 前缀
+pub fn parse_tags(s: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in s.split([',', '，', ' ']) {
+        let tag = tag.trim().trim_start_matches('#');
+        if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+// 解析依赖的任务编号列表
+pub fn parse_ids(s: &str) -> Result<Vec<usize>, TodoError> {
+    s.split([',', '，', ' '])
+        .filter(|id| !id.trim().is_empty())
+        .map(|id| {
+            id.trim()
+                .trim_start_matches('#')
+                .parse::<usize>()
+                .map_err(|_| TodoError::InvalidIndex)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transition_table() {
+        use Status::*;
+        // 每一行是 from，列的顺序和 Status::ALL 相同
+        let table = [
+            (Todo, [false, true, true, true, true, true]),
+            (InProgress, [true, false, true, true, true, true]),
+            (Waiting, [true, true, false, true, true, true]),
+            (Blocked, [true, true, true, false, false, true]),
+            (Done, [true, false, false, false, false, false]),
+            (Cancelled, [true, false, false, false, false, false]),
+        ];
+        for (from, allowed) in table {
+            for (to, allowed) in Status::ALL.into_iter().zip(allowed) {
+                assert_eq!(from.can_transition_to(to), allowed, "{:?} -> {:?}", from, to);
+            }
+        }
+    }
+
+    #[test]
+    fn status_names_parse_back() {
+        for status in Status::ALL {
+            assert_eq!(Status::from_str(status.to_english()).unwrap(), status);
+            assert_eq!(Status::from_str(status.to_string()).unwrap(), status);
+        }
+        assert_eq!(Status::from_str("DOING").unwrap(), Status::InProgress);
+        assert_eq!(Status::from_str("canceled").unwrap(), Status::Cancelled);
+        assert!(matches!(Status::from_str("finished"), Err(TodoError::InvalidStatus(_))));
+    }
+}
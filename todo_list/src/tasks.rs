@@ -0,0 +1,351 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::task::{Priority, Status, Task};
+
+// 任务集合：按编号存放任务，同时维护按优先级、状态、标签、父任务和依赖的索引，
+// 任务很多时查找和统计不用每次遍历全部任务。
+// 编号递增分配，按编号遍历就是添加顺序。保存时和以前一样是任务数组
+#[derive(Debug, Clone, Default)]
+pub struct Tasks {
+    map: BTreeMap<usize, Task>,
+    by_priority: HashMap<Priority, BTreeSet<usize>>,
+    by_status: HashMap<Status, BTreeSet<usize>>,
+    // 标签统一用小写
+    by_tag: HashMap<String, BTreeSet<usize>>,
+    // 父任务 -> 子任务
+    children: HashMap<usize, BTreeSet<usize>>,
+    // 被依赖的任务 -> 依赖它的任务
+    dependents: HashMap<usize, BTreeSet<usize>>,
+    // 每种优先级和状态组合的任务数
+    counts: HashMap<(Priority, Status), usize>,
+}
+
+fn unlink<K: std::hash::Hash + Eq>(index: &mut HashMap<K, BTreeSet<usize>>, key: K, id: usize) {
+    if let Some(ids) = index.get_mut(&key) {
+        ids.remove(&id);
+        if ids.is_empty() {
+            index.remove(&key);
+        }
+    }
+}
+
+impl Tasks {
+    pub fn new() -> Self {
+        Tasks::default()
+    }
+
+    fn link(&mut self, task: &Task) {
+        let id = task.id;
+        self.by_priority.entry(task.priority).or_default().insert(id);
+        self.by_status.entry(task.status).or_default().insert(id);
+        for tag in &task.tags {
+            self.by_tag.entry(tag.to_lowercase()).or_default().insert(id);
+        }
+        if let Some(parent) = task.parent {
+            self.children.entry(parent).or_default().insert(id);
+        }
+        for &dep in &task.depends {
+            self.dependents.entry(dep).or_default().insert(id);
+        }
+        *self.counts.entry((task.priority, task.status)).or_default() += 1;
+    }
+
+    fn unlink(&mut self, task: &Task) {
+        let id = task.id;
+        unlink(&mut self.by_priority, task.priority, id);
+        unlink(&mut self.by_status, task.status, id);
+        for tag in &task.tags {
+            unlink(&mut self.by_tag, tag.to_lowercase(), id);
+        }
+        if let Some(parent) = task.parent {
+            unlink(&mut self.children, parent, id);
+        }
+        for &dep in &task.depends {
+            unlink(&mut self.dependents, dep, id);
+        }
+        if let Some(count) = self.counts.get_mut(&(task.priority, task.status)) {
+            *count -= 1;
+        }
+    }
+
+    // 加入任务，编号已存在时替换，返回被替换的任务
+    pub fn insert(&mut self, task: Task) -> Option<Task> {
+        let old = self.remove(task.id);
+        self.link(&task);
+        self.map.insert(task.id, task);
+        old
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<Task> {
+        let task = self.map.remove(&id)?;
+        self.unlink(&task);
+        Some(task)
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Task> {
+        self.map.get(&id)
+    }
+
+    // 修改一个任务，修改后更新索引
+    pub fn update<R>(&mut self, id: usize, f: impl FnOnce(&mut Task) -> R) -> Option<R> {
+        let mut task = self.remove(id)?;
+        let result = f(&mut task);
+        // 编号由集合管理，修改函数不能改
+        task.id = id;
+        self.insert(task);
+        Some(result)
+    }
+
+    // 修改所有任务，之后重建索引
+    pub fn update_all(&mut self, mut f: impl FnMut(&mut Task)) {
+        let map = std::mem::take(&mut self.map);
+        *self = Tasks::new();
+        for (id, mut task) in map {
+            f(&mut task);
+            task.id = id;
+            self.insert(task);
+        }
+    }
+
+    pub fn retain(&mut self, mut f: impl FnMut(&Task) -> bool) {
+        let removed: Vec<usize> = self.map.values().filter(|task| !f(task)).map(|task| task.id).collect();
+        for id in removed {
+            self.remove(id);
+        }
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.map.contains_key(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    // 按编号（添加顺序）遍历
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Task> + Clone {
+        self.map.values()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.map.keys().copied()
+    }
+
+    pub fn max_id(&self) -> Option<usize> {
+        self.map.keys().next_back().copied()
+    }
+
+    fn lookup<'a>(&'a self, ids: Option<&'a BTreeSet<usize>>) -> impl Iterator<Item = &'a Task> + 'a {
+        ids.into_iter().flatten().filter_map(|id| self.map.get(id))
+    }
+
+    pub fn with_priority(&self, priority: Priority) -> impl Iterator<Item = &Task> {
+        self.lookup(self.by_priority.get(&priority))
+    }
+
+    pub fn with_status(&self, status: Status) -> impl Iterator<Item = &Task> {
+        self.lookup(self.by_status.get(&status))
+    }
+
+    // 标签不区分大小写
+    pub fn with_tag(&self, tag: &str) -> impl Iterator<Item = &Task> {
+        self.lookup(self.by_tag.get(&tag.to_lowercase()))
+    }
+
+    pub fn children(&self, id: usize) -> impl Iterator<Item = &Task> {
+        self.lookup(self.children.get(&id))
+    }
+
+    pub fn dependents(&self, id: usize) -> impl Iterator<Item = &Task> {
+        self.lookup(self.dependents.get(&id))
+    }
+
+    pub fn count_status(&self, status: Status) -> usize {
+        self.by_status.get(&status).map_or(0, BTreeSet::len)
+    }
+
+    pub fn count_tag(&self, tag: &str) -> usize {
+        self.by_tag.get(&tag.to_lowercase()).map_or(0, BTreeSet::len)
+    }
+
+    // 某个优先级下满足状态条件的任务数
+    pub fn count(&self, priority: Priority, status: impl Fn(Status) -> bool) -> usize {
+        Status::ALL
+            .into_iter()
+            .filter(|&s| status(s))
+            .map(|s| self.counts.get(&(priority, s)).copied().unwrap_or(0))
+            .sum()
+    }
+}
+
+impl FromIterator<Task> for Tasks {
+    fn from_iter<I: IntoIterator<Item = Task>>(iter: I) -> Self {
+        let mut tasks = Tasks::new();
+        for task in iter {
+            tasks.insert(task);
+        }
+        tasks
+    }
+}
+
+impl<'a> IntoIterator for &'a Tasks {
+    type Item = &'a Task;
+    type IntoIter = std::collections::btree_map::Values<'a, usize, Task>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.values()
+    }
+}
+
+impl Serialize for Tasks {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.map.values())
+    }
+}
+
+impl<'de> Deserialize<'de> for Tasks {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<Task>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: usize, tags: &[&str], status: Status) -> Task {
+        let mut task = Task::new(id, format!("任务 {}", id), Priority::Medium);
+        task.tags = tags.iter().map(|t| t.to_string()).collect();
+        task.status = status;
+        task
+    }
+
+    fn ids<'a>(tasks: impl Iterator<Item = &'a Task>) -> Vec<usize> {
+        tasks.map(|t| t.id).collect()
+    }
+
+    // 索引和从头重建的结果一致，计数和实际任务一致
+    fn assert_consistent(tasks: &Tasks) {
+        let rebuilt: Tasks = tasks.iter().cloned().collect();
+        assert_eq!(tasks.by_priority, rebuilt.by_priority);
+        assert_eq!(tasks.by_status, rebuilt.by_status);
+        assert_eq!(tasks.by_tag, rebuilt.by_tag);
+        assert_eq!(tasks.children, rebuilt.children);
+        assert_eq!(tasks.dependents, rebuilt.dependents);
+        for priority in [Priority::High, Priority::Medium, Priority::Low] {
+            for status in Status::ALL {
+                let actual = tasks.iter().filter(|t| t.priority == priority && t.status == status).count();
+                assert_eq!(tasks.count(priority, |s| s == status), actual, "{:?} {:?}", priority, status);
+            }
+        }
+    }
+
+    fn sample() -> Tasks {
+        let mut child = task(3, &["home"], Status::Todo);
+        child.parent = Some(1);
+        child.depends = vec![2];
+        child.project = Some("家/装修".to_string());
+        [task(1, &["Work", "urgent"], Status::Todo), task(2, &["work"], Status::InProgress), child]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn insert_indexes_every_field() {
+        let tasks = sample();
+        assert_consistent(&tasks);
+        assert_eq!(ids(tasks.with_tag("WORK")), vec![1, 2]);
+        assert_eq!(tasks.count_tag("work"), 2);
+        assert_eq!(ids(tasks.with_status(Status::Todo)), vec![1, 3]);
+        assert_eq!(ids(tasks.with_priority(Priority::Medium)), vec![1, 2, 3]);
+        assert_eq!(ids(tasks.children(1)), vec![3]);
+        assert_eq!(ids(tasks.dependents(2)), vec![3]);
+        assert_eq!(tasks.max_id(), Some(3));
+    }
+
+    #[test]
+    fn update_moves_the_task_between_indexes() {
+        let mut tasks = sample();
+        tasks.update(1, |task| {
+            task.tags = vec!["home".to_string()];
+            task.status = Status::Done;
+            task.priority = Priority::High;
+        });
+        assert_consistent(&tasks);
+        assert_eq!(ids(tasks.with_tag("work")), vec![2]);
+        assert_eq!(tasks.count_tag("urgent"), 0);
+        assert!(!tasks.by_tag.contains_key("urgent"));
+        assert_eq!(ids(tasks.with_tag("home")), vec![1, 3]);
+        assert_eq!(ids(tasks.with_status(Status::Done)), vec![1]);
+        assert_eq!(ids(tasks.with_status(Status::Todo)), vec![3]);
+        assert_eq!(tasks.count(Priority::High, |s| s.is_closed()), 1);
+
+        // 改项目、父任务和依赖
+        tasks.update(3, |task| {
+            task.project = Some("工作".to_string());
+            task.parent = Some(2);
+            task.depends = vec![1];
+            // 编号不能被修改函数改掉
+            task.id = 99;
+        });
+        assert_consistent(&tasks);
+        assert_eq!(tasks.get(3).unwrap().project.as_deref(), Some("工作"));
+        assert!(tasks.get(99).is_none());
+        assert_eq!(ids(tasks.children(1)), Vec::<usize>::new());
+        assert_eq!(ids(tasks.children(2)), vec![3]);
+        assert_eq!(ids(tasks.dependents(2)), Vec::<usize>::new());
+        assert_eq!(ids(tasks.dependents(1)), vec![3]);
+        assert!(tasks.update(42, |_| ()).is_none());
+    }
+
+    #[test]
+    fn remove_and_replace_unlink_the_old_task() {
+        let mut tasks = sample();
+        assert_eq!(tasks.remove(2).map(|t| t.id), Some(2));
+        assert!(tasks.remove(2).is_none());
+        assert_consistent(&tasks);
+        assert_eq!(ids(tasks.with_tag("work")), vec![1]);
+        assert_eq!(tasks.count_status(Status::InProgress), 0);
+
+        // 同编号再插入时替换旧任务，旧任务的索引一起去掉
+        let old = tasks.insert(task(1, &["new"], Status::Waiting));
+        assert_eq!(old.unwrap().tags, vec!["Work", "urgent"]);
+        assert_consistent(&tasks);
+        assert_eq!(tasks.count_tag("work"), 0);
+        assert_eq!(ids(tasks.with_status(Status::Waiting)), vec![1]);
+
+        tasks.retain(|task| task.id != 3);
+        assert_consistent(&tasks);
+        assert_eq!(ids(tasks.children(1)), Vec::<usize>::new());
+        assert_eq!(tasks.len(), 1);
+    }
+
+    #[test]
+    fn bulk_replace_rebuilds_indexes() {
+        let mut tasks = sample();
+        tasks.update_all(|task| {
+            task.tags.push("Bulk".to_string());
+            task.status = Status::Blocked;
+            task.parent = None;
+        });
+        assert_consistent(&tasks);
+        assert_eq!(ids(tasks.with_tag("bulk")), vec![1, 2, 3]);
+        assert_eq!(tasks.count_status(Status::Blocked), 3);
+        assert_eq!(tasks.count_status(Status::Todo), 0);
+        assert_eq!(ids(tasks.children(1)), Vec::<usize>::new());
+
+        // 整体替换成反序列化的新集合
+        let json = serde_json::to_string(&sample()).unwrap();
+        tasks = serde_json::from_str(&json).unwrap();
+        assert_consistent(&tasks);
+        assert_eq!(ids(tasks.with_tag("work")), vec![1, 2]);
+        assert_eq!(ids(tasks.children(1)), vec![3]);
+        assert_eq!(ids(tasks.iter()), vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,486 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use chacha20poly1305::aead::OsRng;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::date;
+use crate::error::TodoError;
+use crate::estimate::Estimate;
+use crate::task::{Priority, Status, Task};
+use crate::todo::TodoList;
+
+// Taskwarrior 的时间格式，UTC
+const TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+// Taskwarrior 的注释
+#[derive(Debug, Serialize, Deserialize)]
+struct Annotation {
+    #[serde(default)]
+    entry: Option<String>,
+    description: String,
+}
+
+// Taskwarrior 导出的一个任务，没用到的字段会被忽略
+#[derive(Debug, Serialize, Deserialize)]
+struct TwTask {
+    uuid: String,
+    description: String,
+    status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    entry: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    modified: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    end: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wait: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    annotations: Vec<Annotation>,
+    // 旧版本是逗号分隔的字符串，新版本是数组
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    depends: Value,
+    // Taskwarrior 表达不了的受阻状态、父任务（UUID）和工作量预估，作为自定义属性保存
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    todostatus: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    todoparent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    todoestimate: Option<String>,
+}
+
+// 随机生成 UUID（第 4 版）
+pub fn new_uuid() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+fn format_time(time: NaiveDateTime) -> String {
+    match Local.from_local_datetime(&time).earliest() {
+        Some(local) => local.with_timezone(&Utc).format(TIME_FORMAT).to_string(),
+        None => time.format(TIME_FORMAT).to_string(),
+    }
+}
+
+// 解析 Taskwarrior 的时间，转换为本地时间；也接受 RFC 3339 格式
+fn parse_time(s: &str) -> Result<NaiveDateTime, TodoError> {
+    let utc = match NaiveDateTime::parse_from_str(s, TIME_FORMAT) {
+        Ok(time) => Utc.from_utc_datetime(&time),
+        Err(_) => DateTime::parse_from_rfc3339(s)
+            .map_err(|_| TodoError::InvalidDate(s.to_string()))?
+            .with_timezone(&Utc),
+    };
+    Ok(utc.with_timezone(&Local).naive_local())
+}
+
+fn parse_depends(depends: &Value) -> Vec<String> {
+    match depends {
+        Value::String(s) => s.split(',').map(|u| u.trim().to_string()).filter(|u| !u.is_empty()).collect(),
+        Value::Array(list) => list.iter().filter_map(|u| u.as_str()).map(str::to_string).collect(),
+        _ => Vec::new(),
+    }
+}
+
+// 支持 task export 输出的 JSON 数组，也支持每行一个任务
+fn parse(content: &str) -> Result<Vec<TwTask>, TodoError> {
+    let content = content.trim();
+    if content.starts_with('[') {
+        return serde_json::from_str(content).map_err(|e| TodoError::Taskwarrior(e.to_string()));
+    }
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| TodoError::Taskwarrior(format!("第 {} 行：{}", i + 1, e)))
+        })
+        .collect()
+}
+
+// 预估写成能再解析回来的形式，例如 90m、3pt
+fn format_estimate(estimate: Estimate) -> String {
+    match estimate {
+        Estimate::Minutes(minutes) => format!("{}m", minutes),
+        Estimate::Points(points) => format!("{}pt", points),
+    }
+}
+
+// 把 Taskwarrior 任务的字段写到任务上，依赖和父任务在所有任务导入后再处理
+fn apply(task: &mut Task, tw: &TwTask, default_priority: Priority) -> Result<(), TodoError> {
+    let now = date::now();
+    let wait = tw.wait.as_deref().map(parse_time).transpose()?;
+    task.status = match tw.status.as_str() {
+        "completed" => Status::Done,
+        "deleted" => Status::Cancelled,
+        _ if tw.todostatus.as_deref() == Some("blocked") => Status::Blocked,
+        "waiting" => Status::Waiting,
+        _ if wait.is_some_and(|wait| wait > now) => Status::Waiting,
+        _ if tw.start.is_some() => Status::InProgress,
+        "pending" => Status::Todo,
+        other => return Err(TodoError::Taskwarrior(format!("未知的状态 {}", other))),
+    };
+    task.description = tw.description.clone();
+    task.priority = match tw.priority.as_deref() {
+        Some("H") => Priority::High,
+        Some("M") => Priority::Medium,
+        Some("L") => Priority::Low,
+        _ => default_priority,
+    };
+    if let Some(entry) = &tw.entry {
+        task.created = parse_time(entry)?;
+    }
+    if let Some(modified) = &tw.modified {
+        task.modified = Some(parse_time(modified)?);
+    }
+    task.completed = match task.status {
+        Status::Done => tw.end.as_deref().map(parse_time).transpose()?,
+        _ => None,
+    };
+    task.due = tw.due.as_deref().map(parse_time).transpose()?;
+    task.tags = tw.tags.clone();
+    task.project = tw.project.clone();
+    task.estimate = tw.todoestimate.as_deref().map(Estimate::parse).transpose()?;
+    // 导出时备注整体作为一条注释；Taskwarrior 中的多条注释各占一行
+    let notes: Vec<&str> = tw.annotations.iter().map(|a| a.description.as_str()).collect();
+    task.notes = notes.join("\n");
+    task.uuid = Some(tw.uuid.clone());
+    Ok(())
+}
+
+fn to_taskwarrior(todo: &TodoList, task: &Task, uuids: &HashMap<usize, String>) -> TwTask {
+    let now = format_time(date::now());
+    let status = match task.status {
+        Status::Done => "completed",
+        Status::Cancelled => "deleted",
+        Status::Waiting => "waiting",
+        _ => "pending",
+    };
+    let entry = format_time(task.created);
+    let depends: Vec<Value> = task
+        .depends
+        .iter()
+        .filter(|dep| todo.get(**dep).is_some())
+        .map(|dep| Value::String(uuids[dep].clone()))
+        .collect();
+    TwTask {
+        uuid: uuids[&task.id].clone(),
+        description: task.description.clone(),
+        status: status.to_string(),
+        entry: Some(entry.clone()),
+        modified: Some(task.modified.map(format_time).unwrap_or_else(|| now.clone())),
+        start: (task.status == Status::InProgress).then(|| now.clone()),
+        end: task
+            .status
+            .is_closed()
+            .then(|| task.completed.map(format_time).unwrap_or_else(|| now.clone())),
+        // 等待中的任务需要等待时间，没有截止时间时等到一年后
+        wait: (task.status == Status::Waiting).then(|| {
+            format_time(task.due.unwrap_or_else(|| date::now() + chrono::Duration::days(365)))
+        }),
+        due: task.due.map(format_time),
+        priority: Some(
+            match task.priority {
+                Priority::High => "H",
+                Priority::Medium => "M",
+                Priority::Low => "L",
+            }
+            .to_string(),
+        ),
+        project: task.project.clone(),
+        tags: task.tags.clone(),
+        // 备注原样作为一条注释，保留空行
+        annotations: match task.notes.is_empty() {
+            true => Vec::new(),
+            false => vec![Annotation {
+                entry: Some(entry.clone()),
+                description: task.notes.clone(),
+            }],
+        },
+        depends: match depends.is_empty() {
+            true => Value::Null,
+            false => depends.into(),
+        },
+        todostatus: (task.status == Status::Blocked).then(|| "blocked".to_string()),
+        todoparent: task.parent.and_then(|parent| uuids.get(&parent).cloned()),
+        todoestimate: task.estimate.map(format_estimate),
+    }
+}
+
+// 导入 Taskwarrior 的任务：UUID 已存在的任务会被更新，其他的作为新任务添加；
+// 重复任务的模板（recurring）不导入，它生成的任务会正常导入
+pub fn import(todo: &mut TodoList, path: &Path) -> Result<(), TodoError> {
+    let content = if path == Path::new("-") {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        fs::read_to_string(path)?
+    };
+    import_str(todo, &content)
+}
+
+fn import_str(todo: &mut TodoList, content: &str) -> Result<(), TodoError> {
+    let tw_tasks = parse(content)?;
+    let default_priority = todo.config.default_priority;
+
+    let mut ids: HashMap<String, usize> = todo
+        .tasks
+        .iter()
+        .filter_map(|task| task.uuid.clone().map(|uuid| (uuid, task.id)))
+        .collect();
+    let (mut added, mut updated, mut skipped, mut failed) = (0, 0, 0, 0);
+    let mut imported = Vec::new();
+    // 有问题的任务逐个跳过并提示，不影响其他任务
+    for tw in &tw_tasks {
+        if tw.status == "recurring" {
+            skipped += 1;
+            continue;
+        }
+        let existing = ids.get(&tw.uuid).and_then(|&id| todo.get(id)).cloned();
+        let result = match existing {
+            Some(mut task) => apply(&mut task, tw, default_priority).and_then(|_| {
+                let id = task.id;
+                todo.replace_task(task).map(|_| {
+                    updated += 1;
+                    id
+                })
+            }),
+            None => {
+                let mut task = Task::new(0, String::new(), default_priority);
+                apply(&mut task, tw, default_priority)
+                    .and_then(|_| todo.insert_task(task).inspect(|_| added += 1))
+            }
+        };
+        match result {
+            Ok(id) => {
+                ids.insert(tw.uuid.clone(), id);
+                imported.push((id, tw));
+            }
+            Err(e) => {
+                println!("🙅‍♂️{} {}：{}", tw.uuid, tw.description, e);
+                failed += 1;
+            }
+        }
+    }
+
+    // 所有任务都有了编号后再处理依赖和父任务，找不到的会被忽略
+    for (id, tw) in imported {
+        let depends: Vec<usize> = parse_depends(&tw.depends)
+            .iter()
+            .filter_map(|uuid| ids.get(uuid).copied())
+            .filter(|&dep| dep != id)
+            .collect();
+        let parent = tw
+            .todoparent
+            .as_ref()
+            .and_then(|uuid| ids.get(uuid).copied())
+            .filter(|&parent| !is_descendant(todo, parent, id));
+        todo.tasks.update(id, |task| {
+            task.depends = depends;
+            task.parent = parent;
+        });
+    }
+    println!(
+        "📥 从 Taskwarrior 导入完成：新增 {} 个，更新 {} 个，跳过 {} 个",
+        added, updated, skipped
+    );
+    if failed > 0 {
+        println!("⚠️ {} 个任务有错误，没有导入", failed);
+    }
+    Ok(())
+}
+
+// id 是不是 ancestor 自己或它的子孙，用来避免父任务形成环
+fn is_descendant(todo: &TodoList, id: usize, ancestor: usize) -> bool {
+    let mut current = Some(id);
+    while let Some(task) = current {
+        if task == ancestor {
+            return true;
+        }
+        current = todo.get(task).and_then(|task| task.parent);
+    }
+    false
+}
+
+// 和 task export 一样，每个任务占一行
+fn export_string(todo: &TodoList, uuids: &HashMap<usize, String>) -> Result<String, TodoError> {
+    let mut lines = Vec::new();
+    for task in &todo.tasks {
+        let tw = to_taskwarrior(todo, task, uuids);
+        lines.push(serde_json::to_string(&tw)?);
+    }
+    Ok(match lines.is_empty() {
+        true => "[]\n".to_string(),
+        false => format!("[\n{}\n]\n", lines.join(",\n")),
+    })
+}
+
+// 导出为 Taskwarrior 的 JSON 格式，可以直接用 task import 导入；
+// 还没有 UUID 的任务会生成一个并保存，返回任务列表是否被修改
+pub fn export(todo: &mut TodoList, path: Option<&Path>) -> Result<bool, TodoError> {
+    let modified = todo.tasks.iter().any(|task| task.uuid.is_none());
+    if modified {
+        todo.tasks.update_all(|task| {
+            task.uuid.get_or_insert_with(new_uuid);
+        });
+    }
+    let uuids: HashMap<usize, String> = todo
+        .tasks
+        .iter()
+        .filter_map(|task| task.uuid.clone().map(|uuid| (task.id, uuid)))
+        .collect();
+
+    let content = export_string(todo, &uuids)?;
+    match path.filter(|path| *path != Path::new("-")) {
+        Some(path) => {
+            fs::write(path, content)?;
+            println!("📤 已导出 {} 个任务到 {}", todo.tasks.len(), path.display());
+        }
+        None => print!("{}", content),
+    }
+    Ok(modified)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn time(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 5, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn round_trip(todo: &mut TodoList) -> TodoList {
+        todo.tasks.update_all(|task| {
+            task.uuid.get_or_insert_with(new_uuid);
+        });
+        let uuids = todo.tasks.iter().map(|task| (task.id, task.uuid.clone().unwrap())).collect();
+        let content = export_string(todo, &uuids).unwrap();
+        let mut imported = TodoList::new();
+        import_str(&mut imported, &content).unwrap();
+        imported
+    }
+
+    #[test]
+    fn export_and_import_keep_every_field() {
+        let mut todo = TodoList::new();
+        let mut task = Task::new(0, "写周报".to_string(), Priority::High);
+        task.status = Status::InProgress;
+        task.created = time(1, 9);
+        task.notes = "第一行\n\n  缩进的第三行\n".to_string();
+        task.tags = vec!["work".to_string()];
+        task.project = Some("工作/周报".to_string());
+        task.due = Some(time(3, 18));
+        task.estimate = Some(Estimate::Minutes(90));
+        todo.insert_task(task).unwrap();
+        let mut task = Task::new(0, "子任务".to_string(), Priority::Low);
+        task.status = Status::Blocked;
+        task.parent = Some(1);
+        task.depends = vec![1];
+        task.estimate = Some(Estimate::Points(2.5));
+        todo.insert_task(task).unwrap();
+        let mut task = Task::new(0, "完成的".to_string(), Priority::Medium);
+        task.status = Status::Done;
+        task.completed = Some(time(2, 10));
+        todo.insert_task(task).unwrap();
+        let mut task = Task::new(0, "取消的".to_string(), Priority::Medium);
+        task.status = Status::Cancelled;
+        todo.insert_task(task).unwrap();
+
+        let imported = round_trip(&mut todo);
+
+        assert_eq!(imported.tasks.len(), 4);
+        for task in &todo.tasks {
+            let copy = imported.get(task.id).unwrap();
+            assert_eq!(copy.description, task.description);
+            assert_eq!(copy.priority, task.priority);
+            assert_eq!(copy.status, task.status);
+            assert_eq!(copy.notes, task.notes);
+            assert_eq!(copy.tags, task.tags);
+            assert_eq!(copy.project, task.project);
+            assert_eq!(copy.due, task.due);
+            assert_eq!(copy.parent, task.parent);
+            assert_eq!(copy.depends, task.depends);
+            assert_eq!(copy.estimate, task.estimate);
+            assert_eq!(copy.uuid, task.uuid);
+        }
+        assert_eq!(imported.get(1).unwrap().created, time(1, 9));
+        assert_eq!(imported.get(3).unwrap().completed, Some(time(2, 10)));
+    }
+
+    #[test]
+    fn import_reads_taskwarrior_export() {
+        let content = r#"
+{"uuid":"a","description":"Pay bills","status":"pending","priority":"H","tags":["home"],"annotations":[{"entry":"20240501T000000Z","description":"electricity"},{"description":"water"}]}
+{"uuid":"b","description":"File taxes","status":"pending","depends":"a,missing","start":"20240501T000000Z"}
+{"uuid":"c","description":"Weekly review","status":"recurring"}
+{"uuid":"d","description":"Old","status":"completed","end":"20240502T000000Z"}
+"#;
+        let mut todo = TodoList::new();
+        import_str(&mut todo, content).unwrap();
+
+        assert_eq!(todo.tasks.len(), 3);
+        let bills = todo.get(1).unwrap();
+        assert_eq!(bills.priority, Priority::High);
+        assert_eq!(bills.notes, "electricity\nwater");
+        let taxes = todo.get(2).unwrap();
+        assert_eq!(taxes.status, Status::InProgress);
+        assert_eq!(taxes.depends, vec![1]);
+        assert_eq!(todo.get(3).unwrap().status, Status::Done);
+        assert!(todo.get(3).unwrap().completed.is_some());
+
+        // 再次导入时按 UUID 更新已有的任务
+        import_str(&mut todo, r#"[{"uuid":"a","description":"Pay all bills","status":"completed"}]"#).unwrap();
+        assert_eq!(todo.tasks.len(), 3);
+        assert_eq!(todo.get(1).unwrap().description, "Pay all bills");
+        assert_eq!(todo.get(1).unwrap().status, Status::Done);
+    }
+
+    #[test]
+    fn import_skips_bad_records_and_keeps_the_rest() {
+        let content = r#"
+{"uuid":"a","description":"第一个","status":"pending"}
+{"uuid":"b","description":"未知状态","status":"strange"}
+{"uuid":"c","description":"坏的截止时间","status":"pending","due":"tomorrow"}
+{"uuid":"d","description":"坏的预估","status":"pending","todoestimate":"a lot"}
+{"uuid":"e","description":"最后一个","status":"pending","depends":"a,b"}
+"#;
+        let mut todo = TodoList::new();
+        import_str(&mut todo, content).unwrap();
+        let descriptions: Vec<&str> = todo.tasks.iter().map(|task| task.description.as_str()).collect();
+        assert_eq!(descriptions, vec!["第一个", "最后一个"]);
+        assert_eq!(todo.get(2).unwrap().depends, vec![1]);
+
+        // 更新已有任务出错时保留原来的内容
+        import_str(&mut todo, r#"{"uuid":"a","description":"改坏了","status":"strange"}"#).unwrap();
+        assert_eq!(todo.get(1).unwrap().description, "第一个");
+    }
+
+    #[test]
+    fn import_rejects_malformed_json_before_adding_anything() {
+        let mut todo = TodoList::new();
+        let content = "{\"uuid\":\"a\",\"description\":\"x\",\"status\":\"pending\"}\n{\"uuid\": \n";
+        assert!(matches!(import_str(&mut todo, content), Err(TodoError::Taskwarrior(_))));
+        assert!(todo.tasks.is_empty());
+    }
+}
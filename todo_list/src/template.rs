@@ -0,0 +1,511 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, Config};
+use crate::date;
+use crate::error::TodoError;
+use crate::estimate::Estimate;
+use crate::task::{self, Priority, Task};
+use crate::todo::TodoList;
+
+// 模板中的一个任务，文字字段中可以使用 {变量} 占位
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateTask {
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    // 相对创建日期的截止时间，例如 +3d、+1w，也可以写具体日期
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    // 父任务和依赖都写模板中的第几个任务，从 1 开始
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends: Vec<usize>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default, rename = "task")]
+    pub tasks: Vec<TemplateTask>,
+}
+
+// 模板文件，和配置文件放在同一目录下
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Templates {
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(default, rename = "template")]
+    pub templates: Vec<Template>,
+}
+
+// 模板文件：配置文件同目录下的 templates.toml
+pub fn templates_path(config: &Config) -> PathBuf {
+    config.sibling_path("templates.toml")
+}
+
+fn template_task(description: &str, priority: &str, due: &str, parent: Option<usize>) -> TemplateTask {
+    TemplateTask {
+        description: description.to_string(),
+        priority: Some(priority.to_string()),
+        tags: vec!["release".to_string()],
+        due: Some(due.to_string()),
+        estimate: None,
+        project: Some("发布/{version}".to_string()),
+        parent,
+        depends: Vec::new(),
+        notes: String::new(),
+    }
+}
+
+// 还没有模板文件时提供的示例模板
+fn default_templates() -> Vec<Template> {
+    vec![Template {
+        name: "发布".to_string(),
+        description: "版本发布清单".to_string(),
+        tasks: vec![
+            template_task("发布 {version}", "high", "+7d", None),
+            template_task("更新 {version} 的变更日志", "medium", "+3d", Some(1)),
+            template_task("跑完整测试", "high", "+5d", Some(1)),
+            TemplateTask {
+                depends: vec![2, 3],
+                ..template_task("打 {version} 标签并上传", "high", "+7d", Some(1))
+            },
+        ],
+    }]
+}
+
+// 内置变量，不需要用户提供
+const BUILTIN: [&str; 1] = ["date"];
+
+// 逐个处理文字中的 {变量}：replace 返回替换的值，返回 None 时原样保留；
+// 只扫描一遍，替换进去的值不会再被当成变量
+fn expand(s: &str, mut replace: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            let valid = !name.is_empty() && !name.contains(['{', ' ']);
+            valid.then(|| replace(name)).flatten().map(|value| (end, value))
+        });
+        match value {
+            Some((end, value)) => {
+                out.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// 找出文字中的 {变量}
+fn placeholders_in(s: &str, names: &mut Vec<String>) {
+    expand(s, |name| {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        None
+    });
+}
+
+fn substitute(s: &str, vars: &HashMap<String, String>) -> String {
+    expand(s, |name| vars.get(name).cloned())
+}
+
+// 从第 i 个任务出发沿依赖查找，回到正在查找的任务时就是循环
+fn find_cycle(tasks: &[TemplateTask], i: usize, state: &mut [u8]) -> Option<usize> {
+    match state[i] {
+        1 => return Some(i),
+        2 => return None,
+        _ => state[i] = 1,
+    }
+    for &dep in &tasks[i].depends {
+        if (1..=tasks.len()).contains(&dep)
+            && let Some(cycle) = find_cycle(tasks, dep - 1, state)
+        {
+            return Some(cycle);
+        }
+    }
+    state[i] = 2;
+    None
+}
+
+impl Template {
+    // 需要用户提供的变量，按出现的顺序
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for task in &self.tasks {
+            placeholders_in(&task.description, &mut names);
+            placeholders_in(&task.notes, &mut names);
+            for field in [&task.priority, &task.due, &task.estimate, &task.project].into_iter().flatten() {
+                placeholders_in(field, &mut names);
+            }
+            for tag in &task.tags {
+                placeholders_in(tag, &mut names);
+            }
+        }
+        names.retain(|name| !BUILTIN.contains(&name.as_str()));
+        names
+    }
+
+    // 按变量和创建日期生成任务，编号从 first_id 起依次分配
+    fn build(
+        &self,
+        vars: &HashMap<String, String>,
+        base: NaiveDate,
+        first_id: usize,
+        default_priority: Priority,
+    ) -> Result<Vec<Task>, TodoError> {
+        let missing: Vec<String> = self
+            .placeholders()
+            .into_iter()
+            .filter(|name| !vars.contains_key(name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(TodoError::Template(format!(
+                "缺少变量 {}，用 --set 名称=值 指定",
+                missing.join("、")
+            )));
+        }
+        let mut vars = vars.clone();
+        vars.insert("date".to_string(), base.format("%Y-%m-%d").to_string());
+
+        let mut tasks = Vec::new();
+        for (i, item) in self.tasks.iter().enumerate() {
+            let invalid = |what: &str, index: usize| {
+                TodoError::Template(format!("第 {} 个任务的{} {} 无效", i + 1, what, index))
+            };
+            let mut task = Task::new(first_id + i, substitute(&item.description, &vars), default_priority);
+            if let Some(priority) = &item.priority {
+                task.priority = Priority::from_str(&substitute(priority, &vars))?;
+            }
+            task.tags = item.tags.iter().flat_map(|tag| task::parse_tags(&substitute(tag, &vars))).collect();
+            if let Some(due) = &item.due {
+                task.due = Some(date::parse_due_from(&substitute(due, &vars), base)?);
+            }
+            if let Some(estimate) = &item.estimate {
+                task.estimate = Some(Estimate::parse(&substitute(estimate, &vars))?);
+            }
+            task.project = item.project.as_ref().map(|project| substitute(project, &vars));
+            task.notes = substitute(&item.notes, &vars);
+            // 父任务必须排在前面，依赖可以是模板中的任何其他任务
+            if let Some(parent) = item.parent {
+                if parent == 0 || parent > i {
+                    return Err(invalid("父任务", parent));
+                }
+                task.parent = Some(first_id + parent - 1);
+            }
+            for &dep in &item.depends {
+                if dep == 0 || dep > self.tasks.len() || dep == i + 1 {
+                    return Err(invalid("依赖", dep));
+                }
+                task.depends.push(first_id + dep - 1);
+            }
+            tasks.push(task);
+        }
+        // 模板内的依赖不能形成循环，否则创建出的任务永远无法开始
+        let mut state = vec![0; self.tasks.len()];
+        for i in 0..self.tasks.len() {
+            if let Some(cycle) = find_cycle(&self.tasks, i, &mut state) {
+                return Err(TodoError::Template(format!("第 {} 个任务的依赖形成了循环", cycle + 1)));
+            }
+        }
+        Ok(tasks)
+    }
+}
+
+impl Templates {
+    pub fn load(path: &Path) -> Result<Templates, TodoError> {
+        let mut templates = config::read_toml(path)?.unwrap_or_else(|| Templates {
+            path: PathBuf::new(),
+            templates: default_templates(),
+        });
+        templates.path = path.to_path_buf();
+        Ok(templates)
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Template, TodoError> {
+        self.templates
+            .iter()
+            .find(|template| template.name == name)
+            .ok_or_else(|| TodoError::NoTemplate(name.to_string()))
+    }
+}
+
+// 解析 --set 传入的 名称=值
+pub fn parse_vars(pairs: &[String]) -> Result<HashMap<String, String>, TodoError> {
+    pairs
+        .iter()
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => {
+                Ok((name.trim().to_string(), value.to_string()))
+            }
+            _ => Err(TodoError::Template(format!("变量 '{}' 应写成 名称=值", pair))),
+        })
+        .collect()
+}
+
+impl TodoList {
+    pub fn load_templates(&self) -> Result<Templates, TodoError> {
+        Templates::load(&templates_path(&self.config))
+    }
+
+    // 列出所有模板；还没有模板文件时列出的是示例模板，并打印示例文件内容方便照着写
+    pub fn list_templates(&self) -> Result<(), TodoError> {
+        let templates = self.load_templates()?;
+        let cfg = &self.config;
+        if !templates.path.exists() {
+            let example = toml::to_string_pretty(&templates).map_err(|e| TodoError::Config(e.to_string()))?;
+            println!("🧩 还没有模板文件 {}，可以参考下面的示例：\n", templates.path.display());
+            println!("{}", example);
+        }
+        if templates.templates.is_empty() {
+            println!("🧩 还没有模板，可以在 {} 中添加", templates.path.display());
+            return Ok(());
+        }
+        println!("\n{}{}", cfg.icon("🧩 "), cfg.tr("模板：", "Templates:"));
+        for (i, template) in templates.templates.iter().enumerate() {
+            let mut line = format!("{}. {} ({})", i + 1, template.name, template.tasks.len());
+            if !template.description.is_empty() {
+                line.push_str(&format!(" - {}", template.description));
+            }
+            let placeholders = template.placeholders();
+            if !placeholders.is_empty() {
+                line.push_str(&format!(" [{}]", placeholders.join(", ")));
+            }
+            println!("{}", line);
+        }
+        println!("{}: {}", cfg.tr("模板文件", "Template file"), templates.path.display());
+        Ok(())
+    }
+
+    // 用模板创建任务，dry_run 时只预览；被钩子拒绝的任务会跳过，指向它的父任务和依赖也会去掉
+    pub fn instantiate(
+        &mut self,
+        template: &Template,
+        vars: &HashMap<String, String>,
+        base: NaiveDate,
+        dry_run: bool,
+    ) -> Result<(), TodoError> {
+        let tasks = template.build(vars, base, self.next_id, self.config.default_priority)?;
+        if tasks.is_empty() {
+            println!("🧩 模板 '{}' 中没有任务", template.name);
+            return Ok(());
+        }
+        if dry_run {
+            self.preview_tasks(&tasks);
+            println!("\n🔍 预览：将用模板 '{}' 创建 {} 个任务", template.name, tasks.len());
+            return Ok(());
+        }
+
+        // 预先分配的编号 -> 实际编号
+        let mut ids: HashMap<usize, usize> = HashMap::new();
+        let mut created = Vec::new();
+        for mut task in tasks {
+            let planned = task.id;
+            let depends = std::mem::take(&mut task.depends);
+            task.parent = task.parent.and_then(|parent| ids.get(&parent).copied());
+            match self.insert_task(task) {
+                Ok(id) => {
+                    ids.insert(planned, id);
+                    created.push((id, depends));
+                }
+                Err(e) => println!("🙅‍♂️{}", e),
+            }
+        }
+        for (id, depends) in &created {
+            let depends: Vec<usize> = depends.iter().filter_map(|dep| ids.get(dep).copied()).collect();
+            self.tasks.update(*id, |task| task.depends = depends);
+        }
+        println!("🧩 已用模板 '{}' 创建 {} 个任务", template.name, created.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn vars(pairs: &[&str]) -> HashMap<String, String> {
+        parse_vars(&pairs.iter().map(|p| p.to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    fn item(description: &str) -> TemplateTask {
+        TemplateTask {
+            description: description.to_string(),
+            priority: None,
+            tags: Vec::new(),
+            due: None,
+            estimate: None,
+            project: None,
+            parent: None,
+            depends: Vec::new(),
+            notes: String::new(),
+        }
+    }
+
+    fn template(tasks: Vec<TemplateTask>) -> Template {
+        Template {
+            name: "t".to_string(),
+            description: String::new(),
+            tasks,
+        }
+    }
+
+    #[test]
+    fn substitute_fills_placeholders_in_one_pass() {
+        let vars = vars(&["version=1.2", "name={version}", "empty="]);
+        assert_eq!(substitute("发布 {version}", &vars), "发布 1.2");
+        // 替换进去的值不会再被替换
+        assert_eq!(substitute("{name}-{version}", &vars), "{version}-1.2");
+        assert_eq!(substitute("a{empty}b", &vars), "ab");
+        // 未知变量、不是变量的花括号原样保留
+        assert_eq!(substitute("{other} {} { version} {{version}}", &vars), "{other} {} { version} {1.2}");
+        assert_eq!(substitute("{version", &vars), "{version");
+    }
+
+    #[test]
+    fn placeholders_are_listed_in_order() {
+        let mut first = item("发布 {version} {{nested}}");
+        first.project = Some("{team}/{version}".to_string());
+        first.tags = vec!["{date}".to_string()];
+        let mut second = item("{ not } {}");
+        second.notes = "{owner}".to_string();
+        let template = template(vec![first, second]);
+        assert_eq!(template.placeholders(), vec!["version", "nested", "team", "owner"]);
+    }
+
+    #[test]
+    fn build_fills_fields_and_reports_missing_vars() {
+        let mut first = item("发布 {version}");
+        first.priority = Some("{level}".to_string());
+        first.tags = vec!["release,{version}".to_string()];
+        first.project = Some("发布/{version}".to_string());
+        first.notes = "创建于 {date}".to_string();
+        let template = template(vec![first, item("收尾")]);
+
+        match template.build(&vars(&["version=2.0"]), day("2024-05-01"), 1, Priority::Low) {
+            Err(TodoError::Template(message)) => assert!(message.contains("level"), "{}", message),
+            other => panic!("{:?}", other),
+        }
+
+        let tasks = template
+            .build(&vars(&["version=2.0", "level=high"]), day("2024-05-01"), 1, Priority::Low)
+            .unwrap();
+        assert_eq!(tasks[0].description, "发布 2.0");
+        assert_eq!(tasks[0].priority, Priority::High);
+        assert_eq!(tasks[0].tags, vec!["release", "2.0"]);
+        assert_eq!(tasks[0].project.as_deref(), Some("发布/2.0"));
+        assert_eq!(tasks[0].notes, "创建于 2024-05-01");
+        assert_eq!(tasks[1].priority, Priority::Low);
+        assert_eq!(tasks[1].project, None);
+    }
+
+    #[test]
+    fn due_dates_are_relative_to_the_base_date() {
+        let mut tasks = Vec::new();
+        for due in ["+3d", "+1w", "today", "2024-06-30"] {
+            tasks.push(TemplateTask { due: Some(due.to_string()), ..item("x") });
+        }
+        tasks.push(TemplateTask { due: Some("{when}".to_string()), ..item("y") });
+        let built = template(tasks).build(&vars(&["when=+1d"]), day("2024-02-27"), 1, Priority::Medium).unwrap();
+        let dues: Vec<_> = built.iter().map(|t| t.due.unwrap().date()).collect();
+        assert_eq!(
+            dues,
+            vec![day("2024-03-01"), day("2024-03-05"), day("2024-02-27"), day("2024-06-30"), day("2024-02-28")]
+        );
+
+        let bad = template(vec![TemplateTask { due: Some("someday".to_string()), ..item("x") }]);
+        assert!(bad.build(&HashMap::new(), day("2024-02-27"), 1, Priority::Medium).is_err());
+    }
+
+    #[test]
+    fn parents_and_depends_map_to_new_ids() {
+        let template = &default_templates()[0];
+        let tasks = template.build(&vars(&["version=1.0"]), day("2024-05-01"), 10, Priority::Medium).unwrap();
+        let ids: Vec<_> = tasks.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![10, 11, 12, 13]);
+        assert_eq!(tasks[0].parent, None);
+        assert!(tasks[1..].iter().all(|t| t.parent == Some(10)));
+        assert_eq!(tasks[3].depends, vec![11, 12]);
+
+        // 父任务必须在前面，依赖不能指向自己或模板外
+        assert!(template_build(vec![TemplateTask { parent: Some(2), ..item("a") }, item("b")]).is_err());
+        assert!(template_build(vec![TemplateTask { depends: vec![1], ..item("a") }]).is_err());
+        assert!(template_build(vec![TemplateTask { depends: vec![3], ..item("a") }, item("b")]).is_err());
+        assert!(template_build(vec![TemplateTask { depends: vec![0], ..item("a") }]).is_err());
+        // 依赖可以指向后面的任务
+        assert!(template_build(vec![TemplateTask { depends: vec![2], ..item("a") }, item("b")]).is_ok());
+    }
+
+    fn template_build(tasks: Vec<TemplateTask>) -> Result<Vec<Task>, TodoError> {
+        template(tasks).build(&HashMap::new(), day("2024-05-01"), 1, Priority::Medium)
+    }
+
+    #[test]
+    fn dependency_cycles_are_rejected() {
+        let cycle = vec![
+            TemplateTask { depends: vec![3], ..item("a") },
+            TemplateTask { depends: vec![1], ..item("b") },
+            TemplateTask { depends: vec![2], ..item("c") },
+        ];
+        match template_build(cycle) {
+            Err(TodoError::Template(message)) => assert!(message.contains("循环"), "{}", message),
+            other => panic!("{:?}", other),
+        }
+        let two = vec![TemplateTask { depends: vec![2], ..item("a") }, TemplateTask { depends: vec![1], ..item("b") }];
+        assert!(template_build(two).is_err());
+        // 菱形依赖不是循环
+        let diamond = vec![
+            TemplateTask { depends: vec![2, 3], ..item("a") },
+            TemplateTask { depends: vec![4], ..item("b") },
+            TemplateTask { depends: vec![4], ..item("c") },
+            item("d"),
+        ];
+        assert!(template_build(diamond).is_ok());
+    }
+
+    #[test]
+    fn instantiate_keeps_the_dependency_mapping() {
+        let mut todo = TodoList::new();
+        todo.add_task("已有的任务".to_string(), "medium").unwrap();
+        let template = &default_templates()[0];
+        todo.instantiate(template, &vars(&["version=1.0"]), day("2024-05-01"), false).unwrap();
+        assert_eq!(todo.tasks.len(), 5);
+        let tag = todo.get(5).unwrap();
+        assert_eq!(tag.description, "打 1.0 标签并上传");
+        assert_eq!(tag.parent, Some(2));
+        assert_eq!(tag.depends, vec![3, 4]);
+
+        // 预览不创建任务
+        todo.instantiate(template, &vars(&["version=2.0"]), day("2024-05-01"), true).unwrap();
+        assert_eq!(todo.tasks.len(), 5);
+    }
+}
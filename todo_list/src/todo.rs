@@ -0,0 +1,642 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::date;
+use crate::error::TodoError;
+use crate::hooks::HookEvent;
+use crate::sort::SortSpec;
+use crate::table::{Align, Cell, Overflow, Table};
+use crate::task::{Priority, Status, Task, TaskEdit};
+use crate::tasks::Tasks;
+
+// 任务管理器结构体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoList {
+    pub tasks: Tasks,
+    pub next_id: usize,
+    // 运行时配置，不保存到数据文件
+    #[serde(skip)]
+    pub config: Config,
+}
+
+impl Default for TodoList {
+    fn default() -> Self {
+        TodoList::new()
+    }
+}
+
+impl TodoList {
+    // 创建空的代办事项列表
+    pub fn new() -> Self {
+        TodoList {
+            tasks: Tasks::new(),
+            next_id: 1,
+            config: Config::default(),
+        }
+    }
+
+    // 任务列表使用的表格
+    pub fn task_table(&self) -> Table<'_> {
+        let cfg = &self.config;
+        Table::new(cfg)
+            .column(cfg.tr("编号", "ID"), Align::Right, Overflow::Fixed)
+            .column(cfg.tr("状态", "Status"), Align::Left, Overflow::Fixed)
+            .column(cfg.tr("优先级", "Priority"), Align::Left, Overflow::Fixed)
+            .column(cfg.tr("描述", "Description"), Align::Left, Overflow::Wrap)
+            .column(cfg.tr("截止", "Due"), Align::Left, Overflow::Fixed)
+            .column(cfg.tr("标签", "Tags"), Align::Left, Overflow::Truncate)
+    }
+
+    // 一行任务对应的单元格
+    pub fn task_cells(&self, task: &Task) -> Vec<Cell> {
+        let cfg = &self.config;
+        let mut description = task.description.clone();
+        if task.parent.is_some() {
+            description = format!("↳ {}", description);
+        }
+        if !task.notes.is_empty() {
+            description.push_str(if cfg.emoji { " 📝" } else { " +" });
+        }
+        let tags: Vec<String> = task.tags.iter().map(|t| format!("#{}", t)).collect();
+        vec![
+            task.id.to_string().into(),
+            cfg.status_mark(task.status).into(),
+            Cell::colored(cfg.priority_text(task.priority), cfg.priority_color(task.priority)),
+            description.into(),
+            task.due.map(|due| cfg.format_due(due)).unwrap_or_default().into(),
+            tags.join(" ").into(),
+        ]
+    }
+
+    // 根据任务编号查找任务
+    pub fn get(&self, id: usize) -> Option<&Task> {
+        self.tasks.get(id)
+    }
+
+    // 根据任务编号查找任务，找不到时报错
+    fn task(&self, id: usize) -> Result<&Task, TodoError> {
+        self.tasks.get(id).ok_or(TodoError::InvalidIndex)
+    }
+
+    // 添加任务，返回新任务的编号
+    pub fn add_task(&mut self, description: String, priority_str: &str) -> Result<usize, TodoError> {
+        self.add_task_with(description, priority_str, TaskEdit::default())
+    }
+
+    // 添加任务并设置截止时间、标签等其他字段
+    pub fn add_task_with(
+        &mut self,
+        description: String,
+        priority_str: &str,
+        edit: TaskEdit,
+    ) -> Result<usize, TodoError> {
+        let task = self.new_task(description, priority_str, edit)?;
+        let id = self.insert_task(task)?;
+        println!("任务已添加！👌");
+        Ok(id)
+    }
+
+    // 构造一个使用下一个编号的新任务，还没有加入列表
+    pub fn new_task(&self, description: String, priority_str: &str, edit: TaskEdit) -> Result<Task, TodoError> {
+        let priority = Priority::from_str(priority_str)?;
+        let mut task = Task::new(self.next_id, description, priority);
+        self.check_edit(task.id, &edit)?;
+        apply_edit(&mut task, edit);
+        Ok(task)
+    }
+
+    // 用下一个编号加入一个构造好的任务，会执行添加钩子
+    pub fn insert_task(&mut self, mut task: Task) -> Result<usize, TodoError> {
+        task.id = self.next_id;
+        let task = self.run_hooks(HookEvent::Add, task)?;
+        let id = task.id;
+        self.tasks.insert(task);
+        self.next_id += 1;
+        Ok(id)
+    }
+
+    // 用新的内容替换同编号的任务，会执行编辑钩子
+    pub fn replace_task(&mut self, task: Task) -> Result<(), TodoError> {
+        self.task(task.id)?;
+        let task = self.run_hooks(HookEvent::Edit, task)?;
+        self.tasks.insert(task);
+        Ok(())
+    }
+
+    // 执行事件的钩子；钩子改过依赖或父任务时和普通编辑一样检查
+    pub fn run_hooks(&self, event: HookEvent, task: Task) -> Result<Task, TodoError> {
+        let (depends, parent) = (task.depends.clone(), task.parent);
+        let task = self.config.hooks.run(event, task)?;
+        let edit = TaskEdit {
+            depends: (task.depends != depends).then(|| task.depends.clone()),
+            parent: (task.parent != parent).then_some(task.parent),
+            ..Default::default()
+        };
+        self.check_edit(task.id, &edit)?;
+        Ok(task)
+    }
+
+    // 依赖的任务必须存在，且不能直接或间接依赖自身；父任务也不能是自己的子孙任务
+    fn check_edit(&self, id: usize, edit: &TaskEdit) -> Result<(), TodoError> {
+        if let Some(depends) = &edit.depends {
+            for &dep in depends {
+                if self.get(dep).is_none() || self.depends_on(dep, id) {
+                    return Err(TodoError::InvalidDependency(dep));
+                }
+            }
+        }
+        if let Some(Some(parent)) = edit.parent {
+            let mut ancestor = Some(parent);
+            while let Some(current) = ancestor {
+                if current == id || self.get(current).is_none() {
+                    return Err(TodoError::InvalidParent(parent));
+                }
+                ancestor = self.get(current).and_then(|task| task.parent);
+            }
+        }
+        Ok(())
+    }
+
+    // from 是否直接或间接依赖 target（包括 from 就是 target）
+    fn depends_on(&self, from: usize, target: usize) -> bool {
+        let mut seen = HashSet::new();
+        let mut pending = vec![from];
+        while let Some(id) = pending.pop() {
+            if id == target {
+                return true;
+            }
+            if seen.insert(id) {
+                pending.extend(self.get(id).map(|task| task.depends.clone()).unwrap_or_default());
+            }
+        }
+        false
+    }
+
+    // 子任务
+    pub fn subtasks(&self, id: usize) -> Vec<&Task> {
+        self.tasks.children(id).collect()
+    }
+
+    // 删除任务后去掉其他任务对它的引用
+    fn remove_references(&mut self, removed: &[usize]) {
+        let mut affected: Vec<usize> = Vec::new();
+        for &id in removed {
+            affected.extend(self.tasks.children(id).map(|t| t.id));
+            affected.extend(self.tasks.dependents(id).map(|t| t.id));
+        }
+        for id in affected {
+            self.tasks.update(id, |task| {
+                task.depends.retain(|dep| !removed.contains(dep));
+                if task.parent.is_some_and(|parent| removed.contains(&parent)) {
+                    task.parent = None;
+                }
+            });
+        }
+    }
+
+    // 编辑任务
+    pub fn edit_task(&mut self, id: usize, edit: TaskEdit) -> Result<(), TodoError> {
+        let mut task = self.task(id)?.clone();
+        self.check_edit(id, &edit)?;
+
+        apply_edit(&mut task, edit);
+        let task = self.run_hooks(HookEvent::Edit, task)?;
+        println!("✏️ 任务 '{}' 已更新", task.description);
+        self.tasks.insert(task);
+        Ok(())
+    }
+
+    // 是否被未结束的任务阻塞
+    pub fn is_blocked(&self, task: &Task) -> bool {
+        task.status == Status::Blocked
+            || task
+                .depends
+                .iter()
+                .any(|&dep| self.get(dep).is_some_and(|t| !t.status.is_closed()))
+    }
+
+    // 是否阻塞着其他未结束的任务
+    pub fn is_blocking(&self, task: &Task) -> bool {
+        !task.status.is_closed()
+            && self.tasks.dependents(task.id).any(|t| !t.status.is_closed())
+    }
+
+    // 按排序规则打印一组任务
+    pub fn print_tasks(&self, title: &str, mut tasks: Vec<&Task>, sort: &SortSpec) {
+        sort.sort(self, &mut tasks);
+        println!("\n{}", title);
+        let mut table = self.task_table();
+        for task in tasks {
+            table.row(self.task_cells(task));
+        }
+        table.print();
+    }
+
+    // 预览将要添加的任务，包括项目和父任务
+    pub fn preview_tasks(&self, tasks: &[Task]) {
+        let cfg = &self.config;
+        let mut table = Table::new(cfg)
+            .column(cfg.tr("编号", "ID"), Align::Right, Overflow::Fixed)
+            .column(cfg.tr("状态", "Status"), Align::Left, Overflow::Fixed)
+            .column(cfg.tr("优先级", "Priority"), Align::Left, Overflow::Fixed)
+            .column(cfg.tr("项目", "Project"), Align::Left, Overflow::Truncate)
+            .column(cfg.tr("父任务", "Parent"), Align::Right, Overflow::Fixed)
+            .column(cfg.tr("描述", "Description"), Align::Left, Overflow::Wrap)
+            .column(cfg.tr("截止", "Due"), Align::Left, Overflow::Fixed)
+            .column(cfg.tr("标签", "Tags"), Align::Left, Overflow::Truncate);
+        for task in tasks {
+            let tags: Vec<String> = task.tags.iter().map(|t| format!("#{}", t)).collect();
+            table.row(vec![
+                task.id.to_string().into(),
+                cfg.status_mark(task.status).into(),
+                Cell::colored(cfg.priority_text(task.priority), cfg.priority_color(task.priority)),
+                task.project.clone().unwrap_or_default().into(),
+                task.parent.map(|p| p.to_string()).unwrap_or_default().into(),
+                task.description.clone().into(),
+                task.due.map(|due| cfg.format_due(due)).unwrap_or_default().into(),
+                tags.join(" ").into(),
+            ]);
+        }
+        table.print();
+    }
+
+    // 列出所有任务，排序规则为空时按添加顺序
+    pub fn list_tasks(&self, sort: &SortSpec) -> Result<(), TodoError> {
+        if self.tasks.is_empty() {
+            return Err(TodoError::NoTask);
+        }
+        let cfg = &self.config;
+        let title = if sort.is_empty() {
+            cfg.tr("当前任务列表：", "Tasks:").to_string()
+        } else {
+            format!(
+                "{}{}{}",
+                cfg.tr("当前任务列表（排序：", "Tasks (sort: "),
+                sort,
+                cfg.tr("）：", "):")
+            )
+        };
+        let title = format!("{}{}", cfg.icon("📋 "), title);
+        self.print_tasks(&title, self.tasks.iter().collect(), sort);
+        Ok(())
+    }
+
+    // 按照优先级列出任务
+    pub fn list_task_by_priority(&self) -> Result<(), TodoError> {
+        if self.tasks.is_empty() {
+            return Err(TodoError::NoTask);
+        }
+        let title = format!(
+            "{}{}",
+            self.config.icon("📋 "),
+            self.config.tr("按优先级排序的任务列表：", "Tasks by priority:")
+        );
+        // 直接按优先级索引取出，未结束的在前，和按 priority 排序的结果相同
+        let (mut tasks, closed): (Vec<&Task>, Vec<&Task>) = [Priority::High, Priority::Medium, Priority::Low]
+            .into_iter()
+            .flat_map(|priority| self.tasks.with_priority(priority))
+            .partition(|t| !t.status.is_closed());
+        tasks.extend(closed);
+        self.print_tasks(&title, tasks, &SortSpec::default());
+        Ok(())
+    }
+
+    // 任务详情
+    pub fn show_task(&self, id: usize) -> Result<(), TodoError> {
+        let task = self.get(id).ok_or(TodoError::InvalidIndex)?;
+
+        let cfg = &self.config;
+        println!("\n{}{} #{}", cfg.icon("📄 "), cfg.tr("任务详情", "Task"), task.id);
+        println!("{:-<60}", "");
+        println!("{}: {}", cfg.tr("描述", "Description"), task.description);
+        println!("{}: {}", cfg.tr("状态", "Status"), cfg.status_label(task.status));
+        println!("{}: {}", cfg.tr("优先级", "Priority"), cfg.priority_label(task.priority));
+        println!("{}: {}", cfg.tr("创建时间", "Created"), cfg.format_datetime(task.created));
+        if let Some(modified) = task.modified {
+            println!("{}: {}", cfg.tr("修改时间", "Modified"), cfg.format_datetime(modified));
+        }
+        if let Some(completed) = task.completed {
+            println!("{}: {}", cfg.tr("完成时间", "Completed"), cfg.format_datetime(completed));
+        }
+        if let Some(due) = task.due {
+            println!("{}: {}", cfg.tr("截止时间", "Due"), cfg.format_due(due));
+        }
+        if !task.tags.is_empty() {
+            let tags: Vec<String> = task.tags.iter().map(|t| format!("#{}", t)).collect();
+            println!("{}: {}", cfg.tr("标签", "Tags"), tags.join(" "));
+        }
+        if let Some(estimate) = task.estimate {
+            println!("{}: {}", cfg.tr("预估", "Estimate"), estimate);
+        }
+        if let Some(uuid) = &task.uuid {
+            println!("UUID: {}", uuid);
+        }
+        if let Some(project) = &task.project {
+            println!("{}: {}", cfg.tr("项目", "Project"), project);
+        }
+        if let Some(parent) = task.parent.and_then(|id| self.get(id)) {
+            println!("{}: {} {}", cfg.tr("父任务", "Parent"), parent.id, parent.description);
+        }
+        for sub in self.subtasks(task.id) {
+            println!(
+                "{}: {} {} {}",
+                cfg.tr("子任务", "Subtask"),
+                sub.id,
+                cfg.status_mark(sub.status),
+                sub.description
+            );
+        }
+        for dep in &task.depends {
+            if let Some(dep) = self.get(*dep) {
+                println!(
+                    "{}: {} {} {}",
+                    cfg.tr("依赖", "Depends on"),
+                    dep.id,
+                    cfg.status_mark(dep.status),
+                    dep.description
+                );
+            }
+        }
+        println!("{}: {:.1}", cfg.tr("紧急度", "Urgency"), self.urgency(task, date::now()));
+        if !task.pomodoros.is_empty() {
+            println!("{}: {} {}", cfg.tr("番茄数", "Pomodoros"), task.pomodoros.len(), cfg.icon("🍅"));
+        }
+        if !task.notes.is_empty() {
+            println!("{:-<60}", "");
+            println!("{}{}:", cfg.icon("📝 "), cfg.tr("备注", "Notes"));
+            println!("{}", task.notes);
+        }
+        println!("{:-<60}", "");
+        Ok(())
+    }
+
+    // 看板视图：按状态分列展示
+    pub fn list_kanban(&self, sort: &SortSpec) -> Result<(), TodoError> {
+        if self.tasks.is_empty() {
+            return Err(TodoError::NoTask);
+        }
+
+        let cfg = &self.config;
+        let columns: Vec<Vec<&Task>> = Status::ALL
+            .iter()
+            .map(|&status| {
+                let mut column: Vec<&Task> = self.tasks.with_status(status).collect();
+                sort.sort(self, &mut column);
+                column
+            })
+            .collect();
+
+        // 每个状态一列，列宽不够时折行
+        let mut table = Table::new(cfg);
+        for (status, column) in Status::ALL.iter().zip(&columns) {
+            let header = format!("{} ({})", cfg.status_label(*status), column.len());
+            table = table.column(&header, Align::Left, Overflow::Wrap);
+        }
+        let height = columns.iter().map(Vec::len).max().unwrap_or(0);
+        for i in 0..height {
+            let cells = columns
+                .iter()
+                .map(|column| match column.get(i) {
+                    Some(task) => {
+                        let mark = if cfg.emoji {
+                            task.priority.to_emoji()
+                        } else {
+                            cfg.priority_name(task.priority)
+                        };
+                        Cell::colored(
+                            format!("{} {} {}", task.id, mark, task.description),
+                            cfg.priority_color(task.priority),
+                        )
+                    }
+                    None => Cell::default(),
+                })
+                .collect();
+            table.row(cells);
+        }
+
+        println!("\n{}{}", cfg.icon("🗂️ "), cfg.tr("看板视图：", "Kanban:"));
+        table.print();
+        Ok(())
+    }
+
+    // 切换任务状态
+    pub fn set_status(&mut self, id: usize, status: Status) -> Result<(), TodoError> {
+        let mut task = self.task(id)?.clone();
+        if !task.status.can_transition_to(status) {
+            return Err(TodoError::InvalidTransition(task.status, status));
+        }
+
+        task.status = status;
+        task.completed = (status == Status::Done).then(date::now);
+        let event = match status {
+            Status::Done => HookEvent::Complete,
+            _ => HookEvent::Edit,
+        };
+        let task = self.run_hooks(event, task)?;
+        println!(
+            "{} 任务 '{}' 的状态已更新为「{}」",
+            status.to_emoji(),
+            task.description,
+            status.to_string()
+        );
+        self.tasks.insert(task);
+        Ok(())
+    }
+
+    // 标记任务为已完成
+    pub fn complete_task(&mut self, id: usize) -> Result<(), TodoError> {
+        if self.task(id)?.status == Status::Done {
+            println!("⚠️ 任务已经完成！");
+            return Ok(());
+        }
+
+        self.set_status(id, Status::Done)
+    }
+
+    // 删除任务
+    pub fn delete_task(&mut self, id: usize) -> Result<(), TodoError> {
+        self.run_hooks(HookEvent::Delete, self.task(id)?.clone())?;
+        let task = self.tasks.remove(id).ok_or(TodoError::InvalidIndex)?;
+        self.remove_references(&[id]);
+        println!("🗑️ 已删除任务: '{}'", task.description);
+        Ok(())
+    }
+
+    // 批量删除已完成（含已取消）的任务
+    pub fn delete_complete_task(&mut self) -> Result<(), TodoError> {
+        if self.tasks.is_empty() {
+            return Err(TodoError::NoTask);
+        }
+
+        let closed: Vec<Task> = [Status::Done, Status::Cancelled]
+            .into_iter()
+            .flat_map(|status| self.tasks.with_status(status).cloned())
+            .collect();
+        if closed.is_empty() {
+            return Err(TodoError::NoCompletedTask);
+        }
+        // 被钩子拒绝删除的任务保留下来
+        let mut deleted = Vec::new();
+        for task in closed {
+            let id = task.id;
+            match self.run_hooks(HookEvent::Delete, task) {
+                Ok(_) => {
+                    self.tasks.remove(id);
+                    deleted.push(id);
+                }
+                Err(e) => println!("🙅‍♂️{}", e),
+            }
+        }
+        self.remove_references(&deleted);
+        println!("🗑️ 已删除 {} 个已完成或已取消的任务", deleted.len());
+        Ok(())
+    }
+
+    // 搜索任务
+    pub fn search_tasks(&self, keyword: &str, sort: &SortSpec) -> Result<(), TodoError> {
+        let matching_tasks: Vec<_> = self
+            .tasks
+            .iter()
+            .filter(|task| {
+                let keyword = keyword.to_lowercase();
+                task.description.to_lowercase().contains(&keyword)
+                    || task.notes.to_lowercase().contains(&keyword)
+            })
+            .collect();
+        if matching_tasks.is_empty() {
+            println!("🔍 没有找到包含 '{}' 的任务", keyword);
+            return Ok(());
+        }
+        let title = format!(
+            "{}{} '{}':",
+            self.config.icon("🔍 "),
+            self.config.tr("搜索结果 关键词", "Search results for"),
+            keyword
+        );
+        self.print_tasks(&title, matching_tasks, sort);
+        Ok(())
+    }
+
+    // 统计信息
+    pub fn show_stats(&self) {
+        // 任务总数
+        let total = self.tasks.len();
+        // 已完成的任务
+        let completed = self.tasks.count_status(Status::Done);
+        // 待办（未完成且未取消）
+        let pending = total - completed - self.tasks.count_status(Status::Cancelled);
+
+        // 任务优先级
+        let open = |status: Status| !status.is_closed();
+        let high_priority = self.tasks.count(Priority::High, open);
+        let medium_priority = self.tasks.count(Priority::Medium, open);
+        let low_priority = self.tasks.count(Priority::Low, open);
+
+        let cfg = &self.config;
+        println!("\n{}{}", cfg.icon("📊 "), cfg.tr("任务统计:", "Statistics:"));
+        println!("{}: {}", cfg.tr("总任务数", "Total"), total);
+        println!("{}: {} {}", cfg.tr("已完成", "Done"), completed, cfg.icon("✅"));
+        println!("{}: {} {}", cfg.tr("待完成", "Pending"), pending, cfg.icon("⏳"));
+        println!("{}: {} {}", cfg.tr("高优先级待办", "High priority pending"), high_priority, cfg.icon("🔴"));
+        println!("{}: {} {}", cfg.tr("中优先级待办", "Medium priority pending"), medium_priority, cfg.icon("🟡"));
+        println!("{}: {} {}", cfg.tr("低优先级待办", "Low priority pending"), low_priority, cfg.icon("🟢"));
+
+        println!("\n{}", cfg.tr("各状态任务数:", "By status:"));
+        for status in Status::ALL {
+            let count = self.tasks.count_status(status);
+            println!("{}: {} {}", cfg.status_name(status), count, cfg.icon(status.to_emoji()));
+        }
+        self.show_effort_stats();
+        self.show_view_stats();
+        self.show_pomodoro_stats();
+    }
+}
+
+// 把编辑的字段写入任务
+fn apply_edit(task: &mut Task, edit: TaskEdit) {
+    if let Some(description) = edit.description {
+        task.description = description;
+    }
+    if let Some(priority) = edit.priority {
+        task.priority = priority;
+    }
+    if let Some(due) = edit.due {
+        task.due = due;
+    }
+    if let Some(tags) = edit.tags {
+        task.tags = tags;
+    }
+    if let Some(depends) = edit.depends {
+        task.depends = depends;
+    }
+    if let Some(project) = edit.project {
+        task.project = project;
+    }
+    if let Some(parent) = edit.parent {
+        task.parent = parent;
+    }
+    if let Some(estimate) = edit.estimate {
+        task.estimate = estimate;
+    }
+    if let Some(notes) = edit.notes {
+        task.notes = notes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo_with_task() -> TodoList {
+        let mut todo = TodoList::new();
+        todo.add_task("写周报".to_string(), "medium").unwrap();
+        todo
+    }
+
+    fn status(todo: &TodoList) -> (Status, bool) {
+        let task = todo.get(1).unwrap();
+        (task.status, task.completed.is_some())
+    }
+
+    #[test]
+    fn completing_records_the_time_and_reopening_clears_it() {
+        let mut todo = todo_with_task();
+        todo.set_status(1, Status::InProgress).unwrap();
+        assert_eq!(status(&todo), (Status::InProgress, false));
+        todo.complete_task(1).unwrap();
+        assert_eq!(status(&todo), (Status::Done, true));
+        let completed = todo.get(1).unwrap().completed;
+        // 重复完成不改变完成时间
+        todo.complete_task(1).unwrap();
+        assert_eq!(todo.get(1).unwrap().completed, completed);
+
+        todo.set_status(1, Status::Todo).unwrap();
+        assert_eq!(status(&todo), (Status::Todo, false));
+        todo.set_status(1, Status::Cancelled).unwrap();
+        assert_eq!(status(&todo), (Status::Cancelled, false));
+        todo.set_status(1, Status::Todo).unwrap();
+        assert_eq!(status(&todo), (Status::Todo, false));
+    }
+
+    #[test]
+    fn rejected_transitions_leave_the_task_unchanged() {
+        let mut todo = todo_with_task();
+        todo.set_status(1, Status::Blocked).unwrap();
+        assert!(matches!(
+            todo.set_status(1, Status::Done),
+            Err(TodoError::InvalidTransition(Status::Blocked, Status::Done))
+        ));
+        assert_eq!(status(&todo), (Status::Blocked, false));
+
+        todo.set_status(1, Status::Todo).unwrap();
+        todo.set_status(1, Status::Done).unwrap();
+        for to in [Status::InProgress, Status::Waiting, Status::Blocked, Status::Cancelled] {
+            assert!(matches!(todo.set_status(1, to), Err(TodoError::InvalidTransition(Status::Done, _))));
+        }
+        assert_eq!(status(&todo), (Status::Done, true));
+        assert!(matches!(todo.set_status(2, Status::Done), Err(TodoError::InvalidIndex)));
+    }
+}
@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::date;
+use crate::error::TodoError;
+use crate::table::{self, Align, Cell, Overflow, Table};
+use crate::task::{Priority, Status, Task};
+use crate::todo::TodoList;
+
+// 紧急度各项权重，参考 Taskwarrior 的 urgency 系数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UrgencyWeights {
+    pub priority_high: f64,
+    pub priority_medium: f64,
+    pub priority_low: f64,
+    // 截止时间越近越紧急，逾期一周以上取满值
+    pub due: f64,
+    // 任务越老越紧急，age_max_days 天后取满值
+    pub age: f64,
+    pub age_max_days: f64,
+    // 有标签的任务略微加分
+    pub tags: f64,
+    // 特定标签的额外权重，例如 next
+    pub tag_weights: HashMap<String, f64>,
+    // 阻塞着其他未完成任务
+    pub blocking: f64,
+    // 被未完成的任务阻塞，或状态为受阻
+    pub blocked: f64,
+    // 进行中
+    pub active: f64,
+    // 等待中
+    pub waiting: f64,
+    // 四象限视图中，多少天内到期算紧急
+    pub urgent_days: i64,
+}
+
+impl Default for UrgencyWeights {
+    fn default() -> Self {
+        UrgencyWeights {
+            priority_high: 6.0,
+            priority_medium: 3.9,
+            priority_low: 1.8,
+            due: 12.0,
+            age: 2.0,
+            age_max_days: 365.0,
+            tags: 1.0,
+            tag_weights: HashMap::from([("next".to_string(), 15.0)]),
+            blocking: 8.0,
+            blocked: -5.0,
+            active: 4.0,
+            waiting: -3.0,
+            urgent_days: 2,
+        }
+    }
+}
+
+impl UrgencyWeights {
+    // 标签权重的键转为小写，和标签索引、查询一样不区分大小写；大小写不同的重复键取较大的权重
+    pub fn normalize(&mut self) {
+        let mut weights: HashMap<String, f64> = HashMap::new();
+        for (tag, weight) in self.tag_weights.drain() {
+            let entry = weights.entry(tag.to_lowercase()).or_insert(weight);
+            *entry = entry.max(weight);
+        }
+        self.tag_weights = weights;
+    }
+}
+
+// 截止时间系数：14 天后到期为 0.2，逾期 7 天及以上为 1.0，中间线性增长
+fn due_factor(due: NaiveDateTime, now: NaiveDateTime) -> f64 {
+    let days_overdue = (now - due).num_seconds() as f64 / 86400.0;
+    if days_overdue >= 7.0 {
+        1.0
+    } else if days_overdue >= -14.0 {
+        (days_overdue + 14.0) * 0.8 / 21.0 + 0.2
+    } else {
+        0.2
+    }
+}
+
+// 标签系数：1 个 0.8，2 个 0.9，3 个及以上 1.0
+fn tags_factor(count: usize) -> f64 {
+    match count {
+        0 => 0.0,
+        1 => 0.8,
+        2 => 0.9,
+        _ => 1.0,
+    }
+}
+
+impl TodoList {
+    // 计算任务的紧急度，已结束的任务为 0
+    pub fn urgency(&self, task: &Task, now: NaiveDateTime) -> f64 {
+        if task.status.is_closed() {
+            return 0.0;
+        }
+
+        let weights = &self.config.urgency;
+        let mut score = match task.priority {
+            Priority::High => weights.priority_high,
+            Priority::Medium => weights.priority_medium,
+            Priority::Low => weights.priority_low,
+        };
+        if let Some(due) = task.due {
+            score += weights.due * due_factor(due, now);
+        }
+        let age_days = (now - task.created).num_seconds() as f64 / 86400.0;
+        score += weights.age * (age_days / weights.age_max_days).clamp(0.0, 1.0);
+        score += weights.tags * tags_factor(task.tags.len());
+        for tag in &task.tags {
+            score += weights.tag_weights.get(&tag.to_lowercase()).copied().unwrap_or(0.0);
+        }
+        if self.is_blocking(task) {
+            score += weights.blocking;
+        }
+        if self.is_blocked(task) {
+            score += weights.blocked;
+        }
+        match task.status {
+            Status::InProgress => score += weights.active,
+            Status::Waiting => score += weights.waiting,
+            _ => {}
+        }
+        score
+    }
+
+    // 重要：高、中优先级；紧急：临近截止或逾期，或者阻塞着其他任务
+    fn is_important(&self, task: &Task) -> bool {
+        task.priority != Priority::Low
+    }
+
+    fn is_urgent(&self, task: &Task, now: NaiveDateTime) -> bool {
+        let due_soon = task
+            .due
+            .is_some_and(|due| due <= now + Duration::days(self.config.urgency.urgent_days));
+        due_soon || self.is_blocking(task)
+    }
+
+    // 按紧急度从高到低列出未结束的任务
+    pub fn list_by_urgency(&self) -> Result<(), TodoError> {
+        let now = date::now();
+        let mut scored: Vec<(f64, &Task)> = self
+            .tasks
+            .iter()
+            .filter(|t| !t.status.is_closed())
+            .map(|t| (self.urgency(t, now), t))
+            .collect();
+        if scored.is_empty() {
+            return Err(TodoError::NoTask);
+        }
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let cfg = &self.config;
+        println!(
+            "\n{}{}",
+            cfg.icon("🔥 "),
+            cfg.tr("按紧急度排序的任务列表：", "Tasks by urgency:")
+        );
+        let mut table = Table::new(cfg)
+            .column(cfg.tr("紧急度", "Urgency"), Align::Right, Overflow::Fixed)
+            .column(cfg.tr("编号", "ID"), Align::Right, Overflow::Fixed)
+            .column(cfg.tr("状态", "Status"), Align::Left, Overflow::Fixed)
+            .column(cfg.tr("优先级", "Priority"), Align::Left, Overflow::Fixed)
+            .column(cfg.tr("描述", "Description"), Align::Left, Overflow::Wrap);
+        for (score, task) in scored {
+            table.row(vec![
+                format!("{:.1}", score).into(),
+                task.id.to_string().into(),
+                cfg.status_mark(task.status).into(),
+                Cell::colored(cfg.priority_text(task.priority), cfg.priority_color(task.priority)),
+                task.description.as_str().into(),
+            ]);
+        }
+        table.print();
+        Ok(())
+    }
+
+    // 四象限（艾森豪威尔矩阵）视图
+    pub fn show_matrix(&self) -> Result<(), TodoError> {
+        let now = date::now();
+        let mut open: Vec<(f64, &Task)> = self
+            .tasks
+            .iter()
+            .filter(|t| !t.status.is_closed())
+            .map(|t| (self.urgency(t, now), t))
+            .collect();
+        if open.is_empty() {
+            return Err(TodoError::NoTask);
+        }
+        open.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let cfg = &self.config;
+        // 象限顺序：左上、右上、左下、右下
+        let mut quadrants: [Vec<String>; 4] = Default::default();
+        for (_, task) in open {
+            let quadrant = match (self.is_important(task), self.is_urgent(task, now)) {
+                (true, true) => 0,
+                (true, false) => 1,
+                (false, true) => 2,
+                (false, false) => 3,
+            };
+            let mark = if cfg.emoji {
+                task.priority.to_emoji()
+            } else {
+                cfg.priority_name(task.priority)
+            };
+            quadrants[quadrant].push(format!("{} {} {}", task.id, mark, task.description));
+        }
+
+        // 每个象限最宽 36 格，终端较窄时按宽度平分
+        let width = table::terminal_width()
+            .map(|w| (w.saturating_sub(7) / 2).clamp(12, 36))
+            .unwrap_or(36);
+        let titles = [
+            format!("{}{}", cfg.icon("🔥 "), cfg.tr("重要且紧急：立即做", "Urgent & important: do")),
+            format!("{}{}", cfg.icon("📅 "), cfg.tr("重要不紧急：计划做", "Important: schedule")),
+            format!("{}{}", cfg.icon("⚡ "), cfg.tr("紧急不重要：委托做", "Urgent: delegate")),
+            format!("{}{}", cfg.icon("🧹 "), cfg.tr("不重要不紧急：少做", "Neither: drop")),
+        ];
+        let rule = format!("+{:-<w$}+{:-<w$}+", "", "", w = width + 2);
+
+        println!("\n{}{}", cfg.icon("🧭 "), cfg.tr("四象限视图：", "Eisenhower matrix:"));
+        for row in 0..2 {
+            let (left, right) = (row * 2, row * 2 + 1);
+            println!("{}", rule);
+            println!("| {} | {} |", table::pad(&titles[left], width), table::pad(&titles[right], width));
+            println!("{}", rule);
+            let lines = quadrants[left].len().max(quadrants[right].len()).max(1);
+            for i in 0..lines {
+                let l = quadrants[left].get(i).map(String::as_str).unwrap_or("");
+                let r = quadrants[right].get(i).map(String::as_str).unwrap_or("");
+                println!("| {} | {} |", table::pad(l, width), table::pad(r, width));
+            }
+        }
+        println!("{}", rule);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn now() -> NaiveDateTime {
+        date::parse_due("2024-05-08 12:00").unwrap()
+    }
+
+    // 新建的中优先级任务，创建于 now
+    fn todo(count: usize) -> TodoList {
+        let mut todo = TodoList::new();
+        for i in 0..count {
+            todo.add_task(format!("任务{}", i + 1), "medium").unwrap();
+        }
+        todo.tasks.update_all(|task| task.created = now());
+        todo
+    }
+
+    fn score(todo: &TodoList, id: usize) -> f64 {
+        todo.urgency(todo.get(id).unwrap(), now())
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn due_factor_grows_until_a_week_overdue() {
+        let days = |n: i64| now() + Duration::days(n);
+        assert!(close(due_factor(days(30), now()), 0.2));
+        assert!(close(due_factor(days(14), now()), 0.2));
+        assert!(close(due_factor(days(0), now()), 0.2 + 14.0 * 0.8 / 21.0));
+        assert!(close(due_factor(days(-7), now()), 1.0));
+        assert!(close(due_factor(days(-100), now()), 1.0));
+        assert!(due_factor(days(1), now()) < due_factor(days(0), now()));
+    }
+
+    #[test]
+    fn each_component_adds_its_weight() {
+        let mut todo = todo(8);
+        let weights = UrgencyWeights::default();
+        let base = score(&todo, 1);
+        assert!(close(base, weights.priority_medium));
+
+        todo.tasks.update(2, |task| task.priority = Priority::High);
+        assert!(close(score(&todo, 2) - base, weights.priority_high - weights.priority_medium));
+        todo.tasks.update(3, |task| task.due = Some(now() - Duration::days(10)));
+        assert!(close(score(&todo, 3) - base, weights.due));
+        todo.tasks.update(4, |task| task.created = now() - Duration::days(1000));
+        assert!(close(score(&todo, 4) - base, weights.age));
+        todo.tasks.update(5, |task| task.tags = vec!["home".to_string()]);
+        assert!(close(score(&todo, 5) - base, weights.tags * 0.8));
+        todo.tasks.update(6, |task| task.status = Status::InProgress);
+        assert!(close(score(&todo, 6) - base, weights.active));
+        todo.tasks.update(7, |task| task.status = Status::Waiting);
+        assert!(close(score(&todo, 7) - base, weights.waiting));
+
+        // 8 依赖 1：1 阻塞着别人，8 被阻塞
+        todo.tasks.update(8, |task| task.depends = vec![1]);
+        assert!(close(score(&todo, 1) - base, weights.blocking));
+        assert!(close(score(&todo, 8) - base, weights.blocked));
+
+        todo.tasks.update(2, |task| task.status = Status::Done);
+        assert_eq!(score(&todo, 2), 0.0);
+    }
+
+    #[test]
+    fn tag_weights_ignore_case() {
+        let mut todo = todo(2);
+        todo.tasks.update(1, |task| task.tags = vec!["Next".to_string()]);
+        todo.tasks.update(2, |task| task.tags = vec!["ÜBER".to_string()]);
+        let weights = &mut todo.config.urgency;
+        weights.tag_weights.insert("Über".to_string(), 2.0);
+        weights.tag_weights.insert("über".to_string(), 3.0);
+        weights.normalize();
+        let tags = UrgencyWeights::default().tags * 0.8;
+        let base = todo.config.urgency.priority_medium + tags;
+        assert!(close(score(&todo, 1) - base, 15.0));
+        assert!(close(score(&todo, 2) - base, 3.0));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[urgency.tag_weights]\nWork = 5.0\n").unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.urgency.tag_weights.get("work"), Some(&5.0));
+    }
+
+    #[test]
+    fn components_order_tasks() {
+        let mut todo = todo(4);
+        todo.tasks.update(1, |task| task.priority = Priority::Low);
+        todo.tasks.update(2, |task| task.priority = Priority::High);
+        todo.tasks.update(3, |task| {
+            task.priority = Priority::Low;
+            task.due = Some(now() - Duration::days(1));
+        });
+        todo.tasks.update(4, |task| task.tags = vec!["next".to_string()]);
+        let mut ids: Vec<usize> = todo.tasks.ids().collect();
+        ids.sort_by(|&a, &b| score(&todo, b).total_cmp(&score(&todo, a)));
+        assert_eq!(ids, vec![4, 3, 2, 1]);
+    }
+}
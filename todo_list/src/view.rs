@@ -0,0 +1,260 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, Config};
+use crate::error::TodoError;
+use crate::filter::Filter;
+use crate::sort::SortSpec;
+use crate::todo::TodoList;
+
+// 保存的查询
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct View {
+    pub name: String,
+    pub query: String,
+    // 视图默认的排序方式
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub sort: String,
+}
+
+impl View {
+    // 视图的查询和排序；命令行给了排序时覆盖视图自己的排序
+    pub fn resolve(&self, sort: &SortSpec) -> Result<(Filter, SortSpec), TodoError> {
+        let sort = match sort.is_empty() {
+            true => SortSpec::parse(&self.sort)?,
+            false => sort.clone(),
+        };
+        Ok((Filter::parse(&self.query)?, sort))
+    }
+}
+
+// 视图文件，和配置文件放在同一目录下
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Views {
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(default, rename = "view")]
+    pub views: Vec<View>,
+}
+
+// 视图文件：配置文件同目录下的 views.toml
+pub fn views_path(config: &Config) -> PathBuf {
+    config.sibling_path("views.toml")
+}
+
+// 还没有视图文件时提供的默认视图
+fn default_views() -> Vec<View> {
+    vec![
+        View {
+            name: "今日重点".to_string(),
+            query: "priority:high status:pending due:today".to_string(),
+            sort: "due".to_string(),
+        },
+        View {
+            name: "已逾期".to_string(),
+            query: "due:overdue".to_string(),
+            sort: "due".to_string(),
+        },
+    ]
+}
+
+impl Views {
+    pub fn load(path: &Path) -> Result<Views, TodoError> {
+        let mut views = config::read_toml(path)?.unwrap_or_else(|| Views {
+            path: PathBuf::new(),
+            views: default_views(),
+        });
+        views.path = path.to_path_buf();
+        Ok(views)
+    }
+
+    fn save(&self) -> Result<(), TodoError> {
+        config::write_toml(&self.path, self)
+    }
+
+    pub fn get(&self, name: &str) -> Result<&View, TodoError> {
+        self.views
+            .iter()
+            .find(|view| view.name == name)
+            .ok_or_else(|| TodoError::NoView(name.to_string()))
+    }
+
+    // 保存视图，同名视图会被覆盖；保存前检查查询和排序是否有效
+    pub fn set(&mut self, name: &str, query: &str, sort: &str) -> Result<(), TodoError> {
+        let filter = Filter::parse(query)?;
+        SortSpec::parse(sort)?;
+        let view = View {
+            name: name.to_string(),
+            query: filter.to_string(),
+            sort: sort.to_string(),
+        };
+        match self.views.iter_mut().find(|v| v.name == name) {
+            Some(old) => *old = view,
+            None => self.views.push(view),
+        }
+        self.save()?;
+        println!("🔖 视图 '{}' 已保存", name);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<(), TodoError> {
+        self.get(name)?;
+        self.views.retain(|view| view.name != name);
+        self.save()?;
+        println!("🗑️ 已删除视图 '{}'", name);
+        Ok(())
+    }
+}
+
+impl TodoList {
+    pub fn load_views(&self) -> Result<Views, TodoError> {
+        Views::load(&views_path(&self.config))
+    }
+
+    // 视图中的任务数
+    pub fn view_count(&self, view: &View) -> Result<usize, TodoError> {
+        Ok(self.filter_tasks(&Filter::parse(&view.query)?).len())
+    }
+
+    // 列出所有视图和各自的任务数
+    pub fn list_views(&self) -> Result<(), TodoError> {
+        let views = self.load_views()?;
+        if views.views.is_empty() {
+            println!("🔖 还没有保存的视图");
+            return Ok(());
+        }
+        let cfg = &self.config;
+        println!("\n{}{}", cfg.icon("🔖 "), cfg.tr("视图：", "Views:"));
+        for (i, view) in views.views.iter().enumerate() {
+            let count = self.view_count(view)?;
+            println!("{}. {} ({}) - {}", i + 1, view.name, count, view.query);
+        }
+        Ok(())
+    }
+
+    // 按查询条件列出任务，没有指定排序时按添加顺序
+    pub fn list_filtered(&self, title: &str, filter: &Filter, sort: &SortSpec) -> Result<(), TodoError> {
+        let tasks = self.filter_tasks(filter);
+        if tasks.is_empty() {
+            println!("🔍 没有符合 '{}' 的任务", filter);
+            return Ok(());
+        }
+        let title = format!("{}{} [{}]", self.config.icon("🔖 "), title, filter);
+        self.print_tasks(&title, tasks, sort);
+        Ok(())
+    }
+
+    // 显示某个视图，sort 为空时使用视图自己的排序
+    pub fn show_view(&self, name: &str, sort: &SortSpec) -> Result<(), TodoError> {
+        let views = self.load_views()?;
+        let view = views.get(name)?;
+        let (filter, sort) = view.resolve(sort)?;
+        self.list_filtered(&view.name, &filter, &sort)
+    }
+
+    // 统计中显示每个视图的任务数
+    pub fn show_view_stats(&self) {
+        let views = match self.load_views() {
+            Ok(views) if !views.views.is_empty() => views,
+            Ok(_) => return,
+            Err(e) => {
+                println!("🙅‍♂️{}", e);
+                return;
+            }
+        };
+        let cfg = &self.config;
+        println!("\n{}{}", cfg.icon("🔖 "), cfg.tr("视图:", "Views:"));
+        for view in &views.views {
+            match self.view_count(view) {
+                Ok(count) => println!("{}: {}", view.name, count),
+                Err(e) => println!("{}: 🙅‍♂️{}", view.name, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Status;
+
+    fn todo() -> TodoList {
+        let mut todo = TodoList::new();
+        for (description, priority) in [("a", "high"), ("b", "low"), ("c", "high")] {
+            todo.add_task(description.to_string(), priority).unwrap();
+        }
+        todo.set_status(3, Status::Done).unwrap();
+        todo
+    }
+
+    #[test]
+    fn views_are_saved_and_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("views.toml");
+
+        // 没有视图文件时使用默认视图，此时不写文件
+        let mut views = Views::load(&path).unwrap();
+        assert_eq!(views.views.len(), default_views().len());
+        assert!(!path.exists());
+
+        views.set("重要", "priority:high", "-created").unwrap();
+        views.set("重要", "priority:high status:pending", "").unwrap();
+        views.remove("已逾期").unwrap();
+        assert!(matches!(views.remove("已逾期"), Err(TodoError::NoView(_))));
+
+        let loaded = Views::load(&path).unwrap();
+        let names: Vec<_> = loaded.views.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["今日重点", "重要"]);
+        let view = loaded.get("重要").unwrap();
+        assert_eq!(view.query, Filter::parse("priority:high status:pending").unwrap().to_string());
+        assert_eq!(view.sort, "");
+        assert!(matches!(loaded.get("没有"), Err(TodoError::NoView(_))));
+    }
+
+    #[test]
+    fn invalid_views_are_not_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("views.toml");
+        let mut views = Views::load(&path).unwrap();
+        assert!(views.set("坏", "priority:urgent", "").is_err());
+        assert!(views.set("坏", "tag:x", "colour").is_err());
+        assert!(views.get("坏").is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn applying_a_view_filters_tasks() {
+        let todo = todo();
+        let view = View {
+            name: "重要".to_string(),
+            query: "priority:high status:pending".to_string(),
+            sort: String::new(),
+        };
+        let (filter, _) = view.resolve(&SortSpec::default()).unwrap();
+        let ids: Vec<_> = todo.filter_tasks(&filter).iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1]);
+        assert_eq!(todo.view_count(&view).unwrap(), 1);
+
+        let broken = View { query: "priority:urgent".to_string(), ..view };
+        assert!(todo.view_count(&broken).is_err());
+    }
+
+    #[test]
+    fn command_line_sort_overrides_the_view_sort() {
+        let view = View {
+            name: "v".to_string(),
+            query: "tag:work".to_string(),
+            sort: "priority,-due".to_string(),
+        };
+        let (_, sort) = view.resolve(&SortSpec::default()).unwrap();
+        assert_eq!(sort, SortSpec::parse("priority,-due").unwrap());
+        let cli = SortSpec::parse("-created").unwrap();
+        let (_, sort) = view.resolve(&cli).unwrap();
+        assert_eq!(sort, cli);
+
+        // 视图没有排序且命令行也没有给时按添加顺序
+        let plain = View { sort: String::new(), ..view };
+        assert!(plain.resolve(&SortSpec::default()).unwrap().1.is_empty());
+    }
+}